@@ -7,7 +7,7 @@ fn main() {
     write(Fd::stdout, "File: ".as_bytes()).unwrap();
     let path = read(Fd::stdin, &mut buffer).unwrap();
     let path = std::path::Path::new(std::ffi::OsStr::from_bytes(path.strip_suffix(b"\n").unwrap()));
-    let fd = open(path, open::Flags::CREATE | open::Flags::WRITE_ONLY, mode!(rw_ ___ ___)).unwrap();
+    let fd = OpenOptions::new().write(true).create(true).truncate(true).mode(mode!(rw_ ___ ___)).open(path).unwrap();
     loop {
         let data = read(Fd::stdin, &mut buffer).unwrap();
         if data == b"\x1b\n" { break }
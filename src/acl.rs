@@ -0,0 +1,151 @@
+//! Encoding and decoding of POSIX access control lists in the binary format stored in the
+//! `system.posix_acl_access` and `system.posix_acl_default` extended attributes.
+use crate::{c_flags, Error};
+
+/// The extended attribute holding the access ACL of a file.
+pub const ACCESS: &str = "system.posix_acl_access";
+/// The extended attribute holding the default ACL inherited by new files in a directory.
+pub const DEFAULT: &str = "system.posix_acl_default";
+
+const VERSION: u32 = 2;
+const HEADER_LEN: usize = 4;
+const ENTRY_LEN: usize = 8;
+const UNDEFINED_ID: u32 = u32::MAX;
+
+const TAG_USER_OBJECT: u16 = 0x01;
+const TAG_USER: u16 = 0x02;
+const TAG_GROUP_OBJECT: u16 = 0x04;
+const TAG_GROUP: u16 = 0x08;
+const TAG_MASK: u16 = 0x10;
+const TAG_OTHER: u16 = 0x20;
+
+c_flags!{
+    pub Permissions(u16) {
+        EXECUTE = 0x1,
+        WRITE = 0x2,
+        READ = 0x4
+    } _ => Err(Error::EINVAL)
+}
+
+/// Who an ACL entry applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tag {
+    /// The owner of the file.
+    UserObject,
+    /// The user with the given ID.
+    User(u32),
+    /// The owning group of the file.
+    GroupObject,
+    /// The group with the given ID.
+    Group(u32),
+    /// The upper bound of permissions granted by `User`, `GroupObject` and `Group` entries.
+    Mask,
+    /// Everyone not matched by another entry.
+    Other
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry {
+    pub tag: Tag,
+    pub permissions: Permissions
+}
+impl Entry {
+    pub fn new(tag: Tag, permissions: Permissions) -> Self {
+        Self { tag, permissions }
+    }
+    fn decode(entry: &[u8]) -> Result<Self, Error> {
+        let tag = u16::from_le_bytes([entry[0], entry[1]]);
+        let permissions = u16::from_le_bytes([entry[2], entry[3]]);
+        let id = u32::from_le_bytes([entry[4], entry[5], entry[6], entry[7]]);
+        let tag = match tag {
+            TAG_USER_OBJECT => Tag::UserObject,
+            TAG_USER => Tag::User(id),
+            TAG_GROUP_OBJECT => Tag::GroupObject,
+            TAG_GROUP => Tag::Group(id),
+            TAG_MASK => Tag::Mask,
+            TAG_OTHER => Tag::Other,
+            _ => return Err(Error::EINVAL)
+        };
+        Ok(Self { tag, permissions: permissions.try_into()? })
+    }
+    fn encode(&self, entry: &mut [u8]) {
+        let (tag, id) = match self.tag {
+            Tag::UserObject => (TAG_USER_OBJECT, UNDEFINED_ID),
+            Tag::User(id) => (TAG_USER, id),
+            Tag::GroupObject => (TAG_GROUP_OBJECT, UNDEFINED_ID),
+            Tag::Group(id) => (TAG_GROUP, id),
+            Tag::Mask => (TAG_MASK, UNDEFINED_ID),
+            Tag::Other => (TAG_OTHER, UNDEFINED_ID)
+        };
+        entry[0..2].copy_from_slice(&tag.to_le_bytes());
+        entry[2..4].copy_from_slice(&self.permissions.0.to_le_bytes());
+        entry[4..8].copy_from_slice(&id.to_le_bytes());
+    }
+}
+
+/// A validated ACL, borrowed from the value of an ACL extended attribute.
+#[derive(Debug, Clone, Copy)]
+pub struct Acl<'a>(&'a [u8]);
+impl<'a> Acl<'a> {
+    /// Decode the value of an ACL extended attribute.
+    pub fn decode(value: &'a [u8]) -> Result<Self, Error> {
+        if value.len() < HEADER_LEN || !(value.len() - HEADER_LEN).is_multiple_of(ENTRY_LEN) {
+            return Err(Error::EINVAL)
+        }
+        if u32::from_le_bytes([value[0], value[1], value[2], value[3]]) != VERSION {
+            return Err(Error::EOPNOTSUPP)
+        }
+        for entry in value[HEADER_LEN..].chunks_exact(ENTRY_LEN) {
+            Entry::decode(entry)?;
+        }
+        Ok(Self(value))
+    }
+    /// The number of entries in the ACL.
+    pub fn len(&self) -> usize {
+        (self.0.len() - HEADER_LEN) / ENTRY_LEN
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn entries(&self) -> Entries<'a> {
+        Entries(self.0[HEADER_LEN..].chunks_exact(ENTRY_LEN))
+    }
+}
+impl<'a> IntoIterator for Acl<'a> {
+    type Item = Entry;
+    type IntoIter = Entries<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries()
+    }
+}
+
+/// An iterator over the entries of an `Acl`.
+#[derive(Debug, Clone)]
+pub struct Entries<'a>(core::slice::ChunksExact<'a, u8>);
+impl<'a> Iterator for Entries<'a> {
+    type Item = Entry;
+    fn next(&mut self) -> Option<Self::Item> {
+        // Entries were validated when the `Acl` was decoded
+        self.0.next().and_then(|entry| Entry::decode(entry).ok())
+    }
+}
+
+/// The size of an encoded ACL with `entries` entries.
+pub const fn encoded_len(entries: usize) -> usize {
+    HEADER_LEN + entries * ENTRY_LEN
+}
+/// Encode ACL entries in to a buffer, returning the length of the encoded value.
+/// 
+/// The kernel requires entries to be ordered by tag as they are declared in `Tag`, with `User` and `Group` entries
+/// in ascending order of ID. Returns `ERANGE` if the buffer is too small.
+pub fn encode(entries: &[Entry], buffer: &mut [u8]) -> Result<usize, Error> {
+    let len = encoded_len(entries.len());
+    if buffer.len() < len {
+        return Err(Error::ERANGE)
+    }
+    buffer[..HEADER_LEN].copy_from_slice(&VERSION.to_le_bytes());
+    for (entry, buffer) in entries.iter().zip(buffer[HEADER_LEN..len].chunks_exact_mut(ENTRY_LEN)) {
+        entry.encode(buffer);
+    }
+    Ok(len)
+}
@@ -0,0 +1,197 @@
+//! A global allocator built only on `mmap`, for binaries without libc.
+//!
+//! Small objects are carved from slabs of a power-of-two size class, kept on a free list per class. Memory given to
+//! slabs is reused but never returned to the system. Larger objects are mapped directly, unmapped when freed and grown
+//! or shrunk in place with `mremap` where possible.
+//!
+//! ```
+//! #[global_allocator]
+//! static ALLOCATOR: syslib::alloc::Allocator = syslib::alloc::Allocator::new();
+//!
+//! fn main() {
+//!     let mut numbers = vec![1, 2, 3];
+//!     numbers.extend(4..10_000);
+//!     assert_eq!(numbers.len(), 9_999);
+//! }
+//! ```
+use core::{alloc::{GlobalAlloc, Layout}, cell::UnsafeCell, ptr, sync::atomic::{AtomicBool, Ordering}};
+use crate::{mmap_anonymous, mremap, munmap, mmap::{Flags, Protection, RemapFlags, PAGE_SIZE}};
+
+/// The smallest size class, which must fit a free list node.
+const MIN_CLASS: usize = 16;
+/// The largest size class. Larger objects are mapped directly.
+const MAX_CLASS: usize = 2048;
+const CLASSES: usize = (MAX_CLASS / MIN_CLASS).ilog2() as usize + 1;
+/// The size of each mapping that is divided in to objects of a size class.
+const SLAB_SIZE: usize = 64 * 1024;
+
+/// A free object, linked to the next free object of the same size class.
+struct Node {
+    next: *mut Node
+}
+
+/// The free list of a size class, behind a spin lock.
+struct SizeClass {
+    locked: AtomicBool,
+    free: UnsafeCell<*mut Node>
+}
+impl SizeClass {
+    const fn new() -> Self {
+        Self {
+            locked: AtomicBool::new(false),
+            free: UnsafeCell::new(ptr::null_mut())
+        }
+    }
+    fn with<T, F: FnOnce(&mut *mut Node) -> T>(&self, f: F) -> T {
+        while self.locked.compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed).is_err() {
+            core::hint::spin_loop();
+        }
+        // Safety: the lock is held
+        let result = f(unsafe { &mut *self.free.get() });
+        self.locked.store(false, Ordering::Release);
+        result
+    }
+    /// Take an object of `size` bytes, mapping a new slab if the free list is empty.
+    fn pop(&self, size: usize) -> *mut u8 {
+        self.with(|free| unsafe {
+            if free.is_null() {
+                *free = slab(size);
+                if free.is_null() {
+                    return ptr::null_mut()
+                }
+            }
+            let node = *free;
+            *free = (*node).next;
+            node as *mut u8
+        })
+    }
+    /// Return an object to the free list.
+    unsafe fn push(&self, object: *mut u8) {
+        self.with(|free| {
+            let node = object as *mut Node;
+            (*node).next = *free;
+            *free = node;
+        })
+    }
+}
+
+/// Map a slab and link its objects of `size` bytes in to a free list, returning the head or null.
+unsafe fn slab(size: usize) -> *mut Node {
+    let Ok(slab) = mmap_anonymous(0, SLAB_SIZE, Protection::READ | Protection::WRITE, Flags::PRIVATE) else {
+        return ptr::null_mut()
+    };
+    let slab = slab as *mut u8;
+    let count = SLAB_SIZE / size;
+    for i in 0..count {
+        let next = if i + 1 < count { slab.add((i + 1) * size) as *mut Node } else { ptr::null_mut() };
+        (*(slab.add(i * size) as *mut Node)).next = next;
+    }
+    slab as *mut Node
+}
+
+/// The size class index for a layout, or `None` if it must be mapped directly.
+///
+/// Slabs are page-aligned and objects are a power-of-two size, so every object is aligned to its size.
+fn class(layout: Layout) -> Option<usize> {
+    let size = layout.size().max(layout.align()).max(MIN_CLASS).next_power_of_two();
+    (size <= MAX_CLASS).then(|| (size / MIN_CLASS).ilog2() as usize)
+}
+
+/// The length of the direct mapping for a layout.
+fn mapping_len(layout: Layout) -> usize {
+    layout.size().next_multiple_of(PAGE_SIZE)
+}
+
+/// Map a large object directly, trimming the mapping to meet alignments greater than a page.
+unsafe fn map(layout: Layout) -> *mut u8 {
+    let length = mapping_len(layout);
+    let protection = Protection::READ | Protection::WRITE;
+    if layout.align() <= PAGE_SIZE {
+        return mmap_anonymous(0, length, protection, Flags::PRIVATE).map_or(ptr::null_mut(), |address| address as *mut u8)
+    }
+    let padded = length + layout.align() - PAGE_SIZE;
+    let Ok(address) = mmap_anonymous(0, padded, protection, Flags::PRIVATE) else {
+        return ptr::null_mut()
+    };
+    let start = address as usize;
+    let aligned = start.next_multiple_of(layout.align());
+    if aligned > start {
+        let _ = munmap(start as *mut _, aligned - start);
+    }
+    let end = aligned + length;
+    if start + padded > end {
+        let _ = munmap(end as *mut _, start + padded - end);
+    }
+    aligned as *mut u8
+}
+
+/// A `GlobalAlloc` using size-class slabs for small objects and direct mappings for large objects.
+pub struct Allocator {
+    classes: [SizeClass; CLASSES]
+}
+// Safety: the free lists are only accessed with their lock held
+unsafe impl Sync for Allocator {}
+impl Default for Allocator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Allocator {
+    pub const fn new() -> Self {
+        Self {
+            classes: [const { SizeClass::new() }; CLASSES]
+        }
+    }
+}
+unsafe impl GlobalAlloc for Allocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        match class(layout) {
+            Some(class) => self.classes[class].pop(MIN_CLASS << class),
+            None => map(layout)
+        }
+    }
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        match class(layout) {
+            Some(_) => {
+                let object = self.alloc(layout);
+                if !object.is_null() {
+                    object.write_bytes(0, layout.size());
+                }
+                object
+            },
+            // New anonymous mappings are already zeroed
+            None => map(layout)
+        }
+    }
+    unsafe fn dealloc(&self, object: *mut u8, layout: Layout) {
+        match class(layout) {
+            Some(class) => self.classes[class].push(object),
+            None => {
+                let _ = munmap(object as *mut _, mapping_len(layout));
+            }
+        }
+    }
+    unsafe fn realloc(&self, object: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_layout = Layout::from_size_align_unchecked(new_size, layout.align());
+        match (class(layout), class(new_layout)) {
+            (Some(old), Some(new)) if old == new => object,
+            // Moving a mapping only preserves page alignment
+            (None, None) if layout.align() <= PAGE_SIZE => {
+                let (old_len, new_len) = (mapping_len(layout), mapping_len(new_layout));
+                if old_len == new_len {
+                    return object
+                }
+                mremap(object as *mut _, old_len, new_len, RemapFlags::MAY_MOVE, ptr::null_mut())
+                    .map_or(ptr::null_mut(), |address| address as *mut u8)
+            },
+            _ => {
+                let new = self.alloc(new_layout);
+                if !new.is_null() {
+                    ptr::copy_nonoverlapping(object, new, layout.size().min(new_size));
+                    self.dealloc(object, layout);
+                }
+                new
+            }
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! A typed, multi-producer single-consumer channel between processes.
+//!
+//! Messages pass through a lock-free ring in a memfd mapped by every endpoint, so no syscalls are made unless an
+//! endpoint has to wait, when it sleeps on a futex in the shared memory. The memfd can be handed to another process
+//! with `sendmsg`:
+//! ```
+//! use syslib::{channel::Channel, sock::{self, Ancillary, AncillaryType, Level}, *};
+//! # fn main() -> Result<(), Error> {
+//! let (parent, child) = socketpair(sock::Domain::UNIX, sock::Type::STREAM | sock::TypeFlags::CLOSE_ON_EXEC, sock::Protocol::UNSPECIFIED)?;
+//! let mut channel = Channel::<u64>::new(64)?;
+//! let rights = Ancillary::<u32, 1>::with_data(&[channel.file().raw()], AncillaryType::RIGHTS, Level::SOCKET);
+//! sendmsg(&parent, &[IoVec::new(b"c")], Some(&rights), sock::Flags::NONE)?;
+//!
+//! // In the other process
+//! let mut rights = Ancillary::<u32, 1>::new();
+//! let mut byte = [0];
+//! recvmsg(&child, &[IoVecMut::new(&mut byte)], Some(&mut rights), sock::Flags::CLOSE_ON_EXEC)?;
+//! let file = Fd::from_raw(unsafe { rights.items()[0].assume_init() }).owned();
+//! let peer = unsafe { Channel::<u64>::from_file(file)? };
+//! peer.sender().send(42)?;
+//!
+//! assert_eq!(channel.receiver().recv()?, 42);
+//! # Ok(())
+//! # }
+//! ```
+use core::{cell::UnsafeCell, marker::PhantomData, mem::MaybeUninit, ptr, sync::atomic::{AtomicU32, Ordering}, time::Duration};
+use crate::{
+    clock_gettime, fstat, futex_wait, futex_wake, memfd_create_unsafe,
+    mmap::{Flags, Mapping, Protection, PAGE_SIZE},
+    remote::Plain,
+    Clock, Error, File, FutexFlags, MemfdFlags, Seals, Timespec
+};
+
+/// Identifies a memfd created by `Channel::new`.
+const MAGIC: u32 = u32::from_ne_bytes(*b"chan");
+/// The largest capacity, which keeps the distance between positions within an `i32`.
+const MAX_CAPACITY: usize = 1 << 30;
+
+/// A counter on its own cache line, so that the senders and receiver do not contend.
+#[repr(C, align(64))]
+struct Counter(AtomicU32);
+
+/// The shared header page at the start of the memfd.
+#[repr(C)]
+struct Header {
+    magic: AtomicU32,
+    capacity: AtomicU32,
+    /// The size of each slot, so that a peer using a different message type is rejected.
+    slot_len: AtomicU32,
+    /// The position of the next slot to be claimed by a sender.
+    tail: Counter,
+    /// The position of the next slot to be received.
+    head: Counter,
+    /// The number of receivers sleeping on the slot at `head`.
+    receiver_waiting: Counter,
+    /// The number of senders sleeping on a full slot.
+    senders_waiting: Counter
+}
+
+/// A message slot. Its sequence is the position it is next free at, or one past that position once filled.
+#[repr(C)]
+struct Slot<T> {
+    sequence: AtomicU32,
+    value: UnsafeCell<MaybeUninit<T>>
+}
+
+/// A channel of `T` backed by a sealed memfd.
+///
+/// Any number of senders may share a channel, in any number of processes, but only one endpoint may receive.
+#[derive(Debug)]
+pub struct Channel<T> {
+    file: File,
+    mapping: Mapping,
+    capacity: u32,
+    _marker: PhantomData<T>
+}
+impl<T: Plain> Channel<T> {
+    /// Create an empty channel holding up to `capacity` messages, which must be a power of two.
+    ///
+    /// The memfd is sealed so that it cannot be resized, as a peer shrinking it would fault the other mappings.
+    pub fn new(capacity: usize) -> Result<Self, Error> {
+        if !capacity.is_power_of_two() || capacity > MAX_CAPACITY {
+            return Err(Error::EINVAL)
+        }
+        let file = unsafe { memfd_create_unsafe(c"syslib_channel".as_ptr() as *const u8, MemfdFlags::CLOSE_ON_EXEC | MemfdFlags::ALLOW_SEALING)? };
+        file.truncate(Self::mapping_len(capacity)? as u64)?;
+        file.add_seals(Seals::SHRINK | Seals::GROW | Seals::SEAL)?;
+        let channel = Self::map(file, capacity)?;
+        for position in 0..capacity as u32 {
+            channel.slot(position).sequence.store(position, Ordering::Relaxed);
+        }
+        let header = channel.header();
+        header.capacity.store(capacity as u32, Ordering::Relaxed);
+        header.slot_len.store(core::mem::size_of::<Slot<T>>() as u32, Ordering::Relaxed);
+        header.magic.store(MAGIC, Ordering::Release);
+        Ok(channel)
+    }
+    /// Open a channel shared by another process.
+    ///
+    /// The memfd must have been created by `Channel::new` for the same `T`; `EINVAL` is returned if it was not, or if
+    /// it is not sealed against shrinking.
+    ///
+    /// # Safety
+    /// Only one endpoint across all processes may receive, and peers must only modify the channel through its
+    /// endpoints. Positions are bounds-checked, but a peer that breaks the protocol causes data races.
+    pub unsafe fn from_file(file: File) -> Result<Self, Error> {
+        if !file.seals()?.any(Seals::SHRINK) {
+            return Err(Error::EINVAL)
+        }
+        let size = fstat(&file)?.size as usize;
+        if size < PAGE_SIZE {
+            return Err(Error::EINVAL)
+        }
+        // Map just the header to find the capacity, then the whole ring
+        let header = Mapping::from_file(&file, 0..PAGE_SIZE, Protection::READ, Flags::SHARED)?;
+        let header = &*(header.as_ptr() as *const Header);
+        let capacity = header.capacity.load(Ordering::Relaxed) as usize;
+        let valid = header.magic.load(Ordering::Acquire) == MAGIC
+            && header.slot_len.load(Ordering::Relaxed) as usize == core::mem::size_of::<Slot<T>>()
+            && capacity.is_power_of_two()
+            && capacity <= MAX_CAPACITY
+            && Self::mapping_len(capacity)? <= size;
+        if !valid {
+            return Err(Error::EINVAL)
+        }
+        Self::map(file, capacity)
+    }
+    fn mapping_len(capacity: usize) -> Result<usize, Error> {
+        if core::mem::align_of::<Slot<T>>() > PAGE_SIZE {
+            return Err(Error::EINVAL)
+        }
+        let slots = capacity.checked_mul(core::mem::size_of::<Slot<T>>()).ok_or(Error::EINVAL)?;
+        Ok(PAGE_SIZE + slots.next_multiple_of(PAGE_SIZE))
+    }
+    fn map(file: File, capacity: usize) -> Result<Self, Error> {
+        let range = 0..Self::mapping_len(capacity)?;
+        let mapping = unsafe { Mapping::from_file(&file, range, Protection::READ | Protection::WRITE, Flags::SHARED)? };
+        Ok(Self { file, mapping, capacity: capacity as u32, _marker: PhantomData })
+    }
+}
+impl<T> Channel<T> {
+    /// The memfd backing the channel, for sharing with another process.
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+    pub fn capacity(&self) -> usize {
+        self.capacity as usize
+    }
+    /// A sending endpoint. Senders may be copied freely, including to other threads.
+    pub fn sender(&self) -> Sender<'_, T> {
+        Sender(self)
+    }
+    /// The receiving endpoint.
+    pub fn receiver(&mut self) -> Receiver<'_, T> {
+        Receiver(self)
+    }
+    /// Both endpoints, such as for sending from other threads of this process.
+    pub fn split(&mut self) -> (Sender<'_, T>, Receiver<'_, T>) {
+        (Sender(self), Receiver(self))
+    }
+    fn header(&self) -> &Header {
+        unsafe { &*(self.mapping.as_ptr() as *const Header) }
+    }
+    fn slot(&self, position: u32) -> &Slot<T> {
+        let index = (position & (self.capacity - 1)) as usize;
+        unsafe { &*(self.mapping.as_ptr().add(PAGE_SIZE) as *const Slot<T>).add(index) }
+    }
+}
+
+/// The absolute `MONOTONIC` time at which a timeout expires.
+fn deadline(timeout: Duration) -> Result<Timespec, Error> {
+    let now = clock_gettime(Clock::MONOTONIC)?;
+    let nanoseconds = now.nanoseconds + timeout.subsec_nanos() as i64;
+    let seconds = (timeout.as_secs() as i64).saturating_add(now.seconds).saturating_add(nanoseconds / 1_000_000_000);
+    Ok(Timespec::new(seconds, nanoseconds % 1_000_000_000))
+}
+
+/// An endpoint sending to a `Channel`.
+#[derive(Debug)]
+pub struct Sender<'a, T>(&'a Channel<T>);
+impl<'a, T> Clone for Sender<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<'a, T> Copy for Sender<'a, T> {}
+impl<'a, T: Plain> Sender<'a, T> {
+    /// Send a message without waiting, or return `EAGAIN` if the channel is full.
+    pub fn try_send(&self, value: T) -> Result<(), Error> {
+        let header = self.0.header();
+        let mut position = header.tail.0.load(Ordering::Relaxed);
+        loop {
+            let slot = self.0.slot(position);
+            let sequence = slot.sequence.load(Ordering::Acquire);
+            match sequence.wrapping_sub(position) as i32 {
+                0 => match header.tail.0.compare_exchange_weak(position, position.wrapping_add(1), Ordering::Relaxed, Ordering::Relaxed) {
+                    Ok(_) => {
+                        unsafe { ptr::write_volatile(slot.value.get(), MaybeUninit::new(value)) };
+                        slot.sequence.store(position.wrapping_add(1), Ordering::SeqCst);
+                        if header.receiver_waiting.0.load(Ordering::SeqCst) != 0 {
+                            futex_wake(&slot.sequence, u32::MAX, FutexFlags::NONE)?;
+                        }
+                        return Ok(())
+                    },
+                    Err(current) => position = current
+                },
+                // The slot still holds the message from a lap ago
+                distance if distance < 0 => return Err(Error::EAGAIN),
+                // Another sender claimed the slot first
+                _ => position = header.tail.0.load(Ordering::Relaxed)
+            }
+        }
+    }
+    /// Send a message, waiting while the channel is full.
+    pub fn send(&self, value: T) -> Result<(), Error> {
+        self.send_until(value, None)
+    }
+    /// Send a message, waiting while the channel is full, or return `ETIMEDOUT` once `timeout` passes.
+    pub fn send_timeout(&self, value: T, timeout: Duration) -> Result<(), Error> {
+        self.send_until(value, Some(&deadline(timeout)?))
+    }
+    fn send_until(&self, value: T, deadline: Option<&Timespec>) -> Result<(), Error> {
+        let header = self.0.header();
+        loop {
+            match self.try_send(value) {
+                Err(Error::EAGAIN) => (),
+                result => return result
+            }
+            // Announce the wait before checking again, so that the receiver either sees us or frees the slot first
+            header.senders_waiting.0.fetch_add(1, Ordering::SeqCst);
+            let position = header.tail.0.load(Ordering::SeqCst);
+            let slot = self.0.slot(position);
+            let sequence = slot.sequence.load(Ordering::SeqCst);
+            let result = if (sequence.wrapping_sub(position) as i32) < 0 {
+                futex_wait(&slot.sequence, sequence, deadline, FutexFlags::NONE)
+            } else {
+                Ok(())
+            };
+            header.senders_waiting.0.fetch_sub(1, Ordering::SeqCst);
+            match result {
+                Ok(()) | Err(Error::EAGAIN | Error::EINTR) => (),
+                Err(e) => return Err(e)
+            }
+        }
+    }
+}
+
+/// The endpoint receiving from a `Channel`.
+#[derive(Debug)]
+pub struct Receiver<'a, T>(&'a Channel<T>);
+impl<'a, T: Plain> Receiver<'a, T> {
+    /// Receive a message without waiting, or return `EAGAIN` if the channel is empty.
+    pub fn try_recv(&mut self) -> Result<T, Error> {
+        let header = self.0.header();
+        let position = header.head.0.load(Ordering::Relaxed);
+        let slot = self.0.slot(position);
+        if slot.sequence.load(Ordering::Acquire) != position.wrapping_add(1) {
+            return Err(Error::EAGAIN)
+        }
+        // Safety: the sender initialised the slot, and every bit pattern is a valid T
+        let value = unsafe { ptr::read_volatile(slot.value.get()).assume_init() };
+        slot.sequence.store(position.wrapping_add(self.0.capacity), Ordering::SeqCst);
+        header.head.0.store(position.wrapping_add(1), Ordering::Relaxed);
+        if header.senders_waiting.0.load(Ordering::SeqCst) != 0 {
+            futex_wake(&slot.sequence, u32::MAX, FutexFlags::NONE)?;
+        }
+        Ok(value)
+    }
+    /// Receive a message, waiting while the channel is empty.
+    pub fn recv(&mut self) -> Result<T, Error> {
+        self.recv_until(None)
+    }
+    /// Receive a message, waiting while the channel is empty, or return `ETIMEDOUT` once `timeout` passes.
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<T, Error> {
+        self.recv_until(Some(&deadline(timeout)?))
+    }
+    fn recv_until(&mut self, deadline: Option<&Timespec>) -> Result<T, Error> {
+        let header = self.0.header();
+        loop {
+            match self.try_recv() {
+                Err(Error::EAGAIN) => (),
+                result => return result
+            }
+            // Announce the wait before checking again, so that a sender either sees us or fills the slot first
+            header.receiver_waiting.0.fetch_add(1, Ordering::SeqCst);
+            let position = header.head.0.load(Ordering::Relaxed);
+            let slot = self.0.slot(position);
+            let sequence = slot.sequence.load(Ordering::SeqCst);
+            let result = if sequence != position.wrapping_add(1) {
+                futex_wait(&slot.sequence, sequence, deadline, FutexFlags::NONE)
+            } else {
+                Ok(())
+            };
+            header.receiver_waiting.0.fetch_sub(1, Ordering::SeqCst);
+            match result {
+                Ok(()) | Err(Error::EAGAIN | Error::EINTR) => (),
+                Err(e) => return Err(e)
+            }
+        }
+    }
+}
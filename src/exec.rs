@@ -0,0 +1,164 @@
+//! Running programs without allocating, so that it is safe in the child of `vfork`.
+//!
+//! `execve` takes its arguments and environment as null-terminated arrays of pointers to null-terminated strings. An
+//! `Arena` builds these from byte strings in a caller-provided buffer, usually on the stack, before forking.
+//!
+//! ```no_run
+//! use syslib::exec::{self, Arena};
+//!
+//! let mut buffer = [0; 1024];
+//! let mut arena = Arena::new(&mut buffer);
+//! let file = arena.c_str(b"sh").unwrap();
+//! let argv = arena.argv(&["sh", "-c", "exit 3"]).unwrap();
+//! let envp = arena.argv(&["LANG=C"]).unwrap();
+//! let mut stack = [0; 16384];
+//! let pid = unsafe {
+//!     syslib::vfork(&mut stack, || {
+//!         exec::execvpe(file, &argv, &envp, exec::DEFAULT_PATH);
+//!         127
+//!     })
+//! }.unwrap();
+//! let (_, status) = syslib::wait4(pid as i32, syslib::clone::WaitFlags::NONE).unwrap();
+//! assert_eq!(status.exit_code(), Some(3));
+//! ```
+use core::{ffi::CStr, mem::{align_of, size_of}, ptr};
+use crate::{execve, execve_unsafe, fs::PATH_MAX, Error};
+
+/// The search path used by `execvpe` when `PATH` is not set.
+pub const DEFAULT_PATH: &[u8] = b"/bin:/usr/bin";
+/// The maximum length of a file name, excluding the null terminator.
+const NAME_MAX: usize = 255;
+
+/// A null-terminated array of pointers to null-terminated strings, for the arguments or environment of `execve`.
+#[derive(Debug, Clone, Copy)]
+pub struct Argv<'a>(&'a [*const u8]);
+impl Argv<'static> {
+    /// An array with no strings.
+    pub const EMPTY: Self = Self(&[ptr::null()]);
+}
+impl<'a> Argv<'a> {
+    /// The number of strings, excluding the null terminator.
+    pub fn len(&self) -> usize {
+        self.0.len() - 1
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    pub fn as_ptr(&self) -> *const *const u8 {
+        self.0.as_ptr()
+    }
+    pub fn get(&self, index: usize) -> Option<&'a CStr> {
+        self.iter().nth(index)
+    }
+    pub fn iter(&self) -> impl Iterator<Item = &'a CStr> {
+        // Safety: every pointer before the terminator was made from a `CStr` that lives for 'a
+        self.0[..self.len()].iter().map(|&string| unsafe { CStr::from_ptr(string as *const _) })
+    }
+}
+
+/// A bump allocator over a caller-provided buffer, for building the strings and arrays taken by `execve`.
+///
+/// Allocations live as long as the buffer and are never freed individually.
+#[derive(Debug)]
+pub struct Arena<'a> {
+    buffer: &'a mut [u8]
+}
+impl<'a> Arena<'a> {
+    pub fn new(buffer: &'a mut [u8]) -> Self {
+        Self { buffer }
+    }
+    /// The number of bytes left unallocated, some of which may be needed for alignment.
+    pub fn remaining(&self) -> usize {
+        self.buffer.len()
+    }
+    /// Take `len` bytes aligned to `align` from the front of the buffer, or `ERANGE` if they do not fit.
+    fn alloc(&mut self, len: usize, align: usize) -> Result<&'a mut [u8], Error> {
+        let padding = self.buffer.as_ptr().align_offset(align);
+        match padding.checked_add(len) {
+            Some(end) if end <= self.buffer.len() => {
+                let buffer = core::mem::take(&mut self.buffer);
+                let (allocated, rest) = buffer[padding..].split_at_mut(len);
+                self.buffer = rest;
+                Ok(allocated)
+            },
+            _ => Err(Error::ERANGE)
+        }
+    }
+    /// Copy a string in to the arena with a null terminator.
+    ///
+    /// Returns `EINVAL` if the string contains a null byte, or `ERANGE` if the arena is full.
+    pub fn c_str<S: AsRef<[u8]>>(&mut self, string: S) -> Result<&'a CStr, Error> {
+        let string = string.as_ref();
+        if string.contains(&0) {
+            return Err(Error::EINVAL)
+        }
+        let buffer = self.alloc(string.len() + 1, 1)?;
+        buffer[..string.len()].copy_from_slice(string);
+        buffer[string.len()] = 0;
+        Ok(unsafe { CStr::from_bytes_with_nul_unchecked(buffer) })
+    }
+    /// Copy strings in to the arena and build a null-terminated array of pointers to them.
+    ///
+    /// Returns `EINVAL` if a string contains a null byte, or `ERANGE` if the arena is full.
+    pub fn argv<S: AsRef<[u8]>>(&mut self, strings: &[S]) -> Result<Argv<'a>, Error> {
+        let count = strings.len() + 1;
+        let len = count.checked_mul(size_of::<*const u8>()).ok_or(Error::ERANGE)?;
+        let buffer = self.alloc(len, align_of::<*const u8>())?;
+        // Safety: the buffer is aligned for, and covers, `count` pointers, and pointers are valid for any bit pattern
+        let pointers = unsafe { core::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut *const u8, count) };
+        for (pointer, string) in pointers.iter_mut().zip(strings) {
+            *pointer = self.c_str(string)?.as_ptr() as *const u8;
+        }
+        pointers[count - 1] = ptr::null();
+        Ok(Argv(pointers))
+    }
+}
+
+/// Replace the program of the calling process, searching for `file` in `search_path` if it does not contain a slash.
+///
+/// `search_path` is a colon-separated list of directories, where an empty entry is the current directory. It is
+/// usually the value of `PATH`, or `DEFAULT_PATH` if that is not set, and should be read before forking.
+///
+/// Only returns on failure, with the error. As with `execvpe` in C, directories where the file is missing or may
+/// not be executed are skipped, and the search stops at any other error. Once every directory has been tried, returns
+/// `EACCES` if the file was found in any of them but could not be executed, or otherwise the last error, usually
+/// `ENOENT`. Unlike glibc, a file that is not a valid executable is not run with `/bin/sh`, and `ENOEXEC` is
+/// returned. Nothing is allocated, so this may be called in the child of `vfork`.
+pub fn execvpe(file: &CStr, argv: &Argv, envp: &Argv, search_path: &[u8]) -> Error {
+    let name = file.to_bytes();
+    if name.is_empty() {
+        return Error::ENOENT
+    }
+    if name.contains(&b'/') {
+        return execve(file, argv, envp)
+    }
+    if name.len() > NAME_MAX {
+        return Error::ENAMETOOLONG
+    }
+    let mut buffer = [0; PATH_MAX];
+    let mut denied = false;
+    let mut error = Error::ENOENT;
+    for dir in search_path.split(|&b| b == b':') {
+        let dir = if dir.is_empty() { &b"."[..] } else { dir };
+        let len = dir.len() + 1 + name.len();
+        // A directory too long to hold the file cannot contain it
+        let Some(path) = buffer.get_mut(..len + 1) else {
+            continue
+        };
+        path[..dir.len()].copy_from_slice(dir);
+        path[dir.len()] = b'/';
+        path[dir.len() + 1..len].copy_from_slice(name);
+        path[len] = 0;
+        match unsafe { execve_unsafe(path.as_ptr(), argv.as_ptr(), envp.as_ptr()) } {
+            Error::EACCES => denied = true,
+            // The file or directory is missing, or on an unavailable file system
+            e @ (Error::ENOENT | Error::ENOTDIR | Error::ESTALE | Error::ENODEV | Error::ETIMEDOUT) => error = e,
+            e => return e
+        }
+    }
+    if denied {
+        Error::EACCES
+    } else {
+        error
+    }
+}
@@ -0,0 +1,379 @@
+//! Helpers for working with the file system, built only on syslib's syscalls.
+//! 
+//! The functions mirror `std::fs`. With `std` they take an `AsRef<Path>`, and each has a `_bytes` form that takes the
+//! path as bytes and is available without `std`. Paths are copied on to the stack rather than the heap, and the `_into`
+//! variants read in to a caller-provided buffer, so that only `read`, `read_to_string` and `canonicalize` need an
+//! allocator, through either `std` or the `alloc` feature.
+#[cfg(any(feature = "std", feature = "alloc"))]
+extern crate alloc;
+#[cfg(any(feature = "std", feature = "alloc"))]
+use alloc::{string::String, vec::Vec};
+#[cfg(feature = "std")]
+use std::path::Path;
+use crate::{fsync, open, AtFlags, Error, Fd, FileDescriptor, FileType, RenameFlags, Stat, UnlinkFlags};
+
+/// The number of temporary file names to try before giving up.
+const TEMPORARY_ATTEMPTS: u32 = 64;
+/// The maximum length of a path, including the null terminator.
+pub const PATH_MAX: usize = 4096;
+/// The amount `read` grows its buffer by when a file is larger than its metadata reported.
+#[cfg(any(feature = "std", feature = "alloc"))]
+const CHUNK_LEN: usize = 8192;
+
+/// Write the whole buffer to a file, retrying on partial writes.
+pub fn write_all<'a, F: AsRef<Fd<'a>>>(fd: F, mut buffer: &[u8]) -> Result<(), Error> {
+    let fd = fd.as_ref();
+    while !buffer.is_empty() {
+        match crate::write(fd, buffer) {
+            Ok(0) => return Err(Error::EIO),
+            Ok(count) => buffer = &buffer[count..],
+            Err(Error::EINTR) => (),
+            Err(e) => return Err(e)
+        }
+    }
+    Ok(())
+}
+
+/// Atomically replace the contents of the file `name` in the directory `dir`.
+/// 
+/// The data is written to an unnamed `O_TMPFILE` file in `dir` and flushed to the storage device before
+/// being linked in to place through `/proc/self/fd`. If unnamed temporary files are not supported a named temporary
+/// file is used instead. Finally `dir` is flushed so that the new directory entry is durable.
+/// 
+/// Readers will observe either the complete old contents or the complete new contents, even across a crash.
+/// 
+/// `dir` must be opened for reading so that it can be flushed. `name` must be a single path component.
+/// A new file is created with mode `0o666`, less the process umask.
+pub fn atomic_write_bytes<'a, D: AsRef<Fd<'a>>, N: AsRef<[u8]>>(dir: D, name: N, buffer: &[u8]) -> Result<(), Error> {
+    let dir = dir.as_ref();
+    let name = name.as_ref();
+    if matches!(name, b"" | b"." | b"..") || name.contains(&b'/') {
+        return Err(Error::EINVAL)
+    }
+    let mode = open::Mode(0o666);
+
+    let flags = open::Flags::TMPFILE | open::Flags::WRITE_ONLY | open::Flags::CLOSE_ON_EXEC;
+    let file = match unsafe { crate::openat_unsafe(dir, c".".as_ptr().cast(), flags, mode) } {
+        Ok(file) => file,
+        // The file system does not support unnamed temporary files
+        Err(Error::EOPNOTSUPP | Error::EISDIR) => return atomic_write_named(dir, name, buffer, mode),
+        Err(e) => return Err(e)
+    };
+    write_all(&file, buffer)?;
+    file.sync()?;
+
+    let fd_path = proc_fd_path(file.raw());
+    let link = |temporary| unsafe { crate::linkat_unsafe(Fd::cwd, fd_path.as_ptr(), dir, temporary, AtFlags::SYMLINK_FOLLOW) };
+    let temporary = match temporary(name, link) {
+        Ok((temporary, ())) => temporary,
+        // `/proc` is not mounted
+        Err(Error::ENOENT) => return atomic_write_named(dir, name, buffer, mode),
+        Err(e) => return Err(e)
+    };
+    publish(dir, &temporary, name)
+}
+#[cfg(feature = "std")]
+pub fn atomic_write<'a, D: AsRef<Fd<'a>>, N: AsRef<Path>>(dir: D, name: N, buffer: &[u8]) -> Result<(), Error> {
+    atomic_write_bytes(dir, bytes(name.as_ref()), buffer)
+}
+/// Atomically replace the contents of a file through a named temporary file.
+fn atomic_write_named(dir: &Fd, name: &[u8], buffer: &[u8], mode: open::Mode) -> Result<(), Error> {
+    let flags = open::Flags::CREATE | open::Flags::EXCLUSIVE | open::Flags::WRITE_ONLY | open::Flags::CLOSE_ON_EXEC;
+    let (temporary, file) = temporary(name, |temporary| unsafe { crate::openat_unsafe(dir, temporary, flags, mode) })?;
+    if let Err(e) = write_all(&file, buffer).and_then(|_| file.sync()) {
+        let _ = unsafe { crate::unlinkat_unsafe(dir, temporary.as_ptr(), UnlinkFlags::NONE) };
+        return Err(e)
+    }
+    publish(dir, &temporary, name)
+}
+/// Rename a temporary file over `name`, removing it on failure, and flush the directory.
+fn publish(dir: &Fd, temporary: &[u8; PATH_MAX], name: &[u8]) -> Result<(), Error> {
+    let result = with_path(name, |name| unsafe { crate::renameat2_unsafe(dir, temporary.as_ptr(), dir, name, RenameFlags::NONE) });
+    if let Err(e) = result {
+        let _ = unsafe { crate::unlinkat_unsafe(dir, temporary.as_ptr(), UnlinkFlags::NONE) };
+        return Err(e)
+    }
+    fsync(dir)
+}
+/// Find an unused temporary name next to `name`, passing each null-terminated candidate to `f` until it does not fail
+/// with `EEXIST`.
+fn temporary<T, F: FnMut(*const u8) -> Result<T, Error>>(name: &[u8], mut f: F) -> Result<([u8; PATH_MAX], T), Error> {
+    const SUFFIX: &[u8] = b".tmp";
+    let mut temporary = [0; PATH_MAX];
+    // A leading dot, the name, a dot, up to two digits and the suffix, then the null terminator
+    if 1 + name.len() + 3 + SUFFIX.len() + 1 > PATH_MAX {
+        return Err(Error::ENAMETOOLONG)
+    }
+    temporary[0] = b'.';
+    temporary[1..1 + name.len()].copy_from_slice(name);
+    for attempt in 0..TEMPORARY_ATTEMPTS {
+        let mut len = 1 + name.len();
+        temporary[len] = b'.';
+        len += 1;
+        if attempt >= 10 {
+            temporary[len] = b'0' + (attempt / 10) as u8;
+            len += 1;
+        }
+        temporary[len] = b'0' + (attempt % 10) as u8;
+        len += 1;
+        temporary[len..len + SUFFIX.len()].copy_from_slice(SUFFIX);
+        temporary[len + SUFFIX.len()] = 0;
+        match f(temporary.as_ptr()) {
+            Err(Error::EEXIST) => continue,
+            result => return result.map(|t| (temporary, t))
+        }
+    }
+    Err(Error::EEXIST)
+}
+
+/// The null-terminated path of the magic link to a file descriptor in `/proc`.
+pub(crate) fn proc_fd_path(fd: u32) -> [u8; 25] {
+    const PREFIX: &[u8] = b"/proc/self/fd/";
+    let digits = fd.checked_ilog10().unwrap_or(0) as usize + 1;
+    let mut path = [0; 25];
+    path[..PREFIX.len()].copy_from_slice(PREFIX);
+    let mut n = fd;
+    for digit in path[PREFIX.len()..PREFIX.len() + digits].iter_mut().rev() {
+        *digit = b'0' + (n % 10) as u8;
+        n /= 10;
+    }
+    path
+}
+/// The bytes of a path.
+#[cfg(feature = "std")]
+fn bytes(path: &Path) -> &[u8] {
+    use std::os::unix::prelude::OsStrExt;
+    path.as_os_str().as_bytes()
+}
+/// Call `f` with a null-terminated copy of `path` on the stack.
+/// 
+/// Returns `ENAMETOOLONG` if the path does not fit in `PATH_MAX` and `EINVAL` if it contains a null byte.
+fn with_path<T, F: FnOnce(*const u8) -> Result<T, Error>>(path: &[u8], f: F) -> Result<T, Error> {
+    if path.contains(&0) {
+        return Err(Error::EINVAL)
+    }
+    let mut buffer = [0; PATH_MAX];
+    buffer.get_mut(..path.len() + 1).ok_or(Error::ENAMETOOLONG)?[..path.len()].copy_from_slice(path);
+    f(buffer.as_ptr())
+}
+/// Open a file for reading.
+fn open_read(path: &[u8]) -> Result<crate::File, Error> {
+    with_path(path, |path| unsafe { crate::open_unsafe(path, open::Flags::READ_ONLY | open::Flags::CLOSE_ON_EXEC, open::Mode::NONE) })
+}
+/// The parent directory of a path, as with `std::path::Path::parent`.
+/// 
+/// Returns `None` for the root and the empty path, and the empty path for a relative path with one component.
+fn parent(path: &[u8]) -> Option<&[u8]> {
+    let end = path.iter().rposition(|&b| b != b'/')?;
+    match path[..end].iter().rposition(|&b| b == b'/') {
+        Some(slash) => match path[..slash].iter().rposition(|&b| b != b'/') {
+            Some(last) => Some(&path[..last + 1]),
+            None => Some(&path[..1])
+        },
+        None => Some(b"")
+    }
+}
+/// Read in to the buffer until it is full or the end of the file is reached, retrying on `EINTR`.
+fn fill<'a, F: AsRef<Fd<'a>>>(fd: F, buffer: &mut [u8]) -> Result<usize, Error> {
+    let fd = fd.as_ref();
+    let mut len = 0;
+    while len < buffer.len() {
+        match crate::read(fd, &mut buffer[len..]) {
+            Ok([]) => break,
+            Ok(read) => len += read.len(),
+            Err(Error::EINTR) => (),
+            Err(e) => return Err(e)
+        }
+    }
+    Ok(len)
+}
+
+/// Read the entire contents of a file.
+/// 
+/// The buffer is sized from the file's metadata, but files that misreport their size, such as those in `/proc`,
+/// are still read in full.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn read_bytes<P: AsRef<[u8]>>(path: P) -> Result<Vec<u8>, Error> {
+    let file = open_read(path.as_ref())?;
+    let size = crate::fstat(&file)?.size.max(0) as usize;
+    // One spare byte lets the final read observe the end of the file without growing the buffer
+    let mut buffer = Vec::with_capacity(size + 1);
+    loop {
+        if buffer.len() == buffer.capacity() {
+            buffer.reserve(CHUNK_LEN);
+        }
+        let spare = buffer.spare_capacity_mut();
+        match unsafe { crate::read_uninit(&file, spare.as_mut_ptr() as *mut u8, spare.len()) } {
+            Ok([]) => return Ok(buffer),
+            Ok(read) => {
+                let len = buffer.len() + read.len();
+                unsafe { buffer.set_len(len) }
+            },
+            Err(Error::EINTR) => (),
+            Err(e) => return Err(e)
+        }
+    }
+}
+#[cfg(feature = "std")]
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<u8>, Error> {
+    read_bytes(bytes(path.as_ref()))
+}
+/// Read the entire contents of a file in to a buffer, returning the filled portion.
+/// 
+/// Returns `ERANGE` if the buffer is too small.
+pub fn read_into_bytes<P: AsRef<[u8]>>(path: P, buffer: &mut [u8]) -> Result<&[u8], Error> {
+    let file = open_read(path.as_ref())?;
+    let len = fill(&file, buffer)?;
+    if len == buffer.len() && fill(&file, &mut [0])? != 0 {
+        return Err(Error::ERANGE)
+    }
+    Ok(&buffer[..len])
+}
+#[cfg(feature = "std")]
+pub fn read_into<P: AsRef<Path>>(path: P, buffer: &mut [u8]) -> Result<&[u8], Error> {
+    read_into_bytes(bytes(path.as_ref()), buffer)
+}
+/// Read the entire contents of a file as UTF-8.
+/// 
+/// Returns `EILSEQ` if the contents are not valid UTF-8.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn read_to_string_bytes<P: AsRef<[u8]>>(path: P) -> Result<String, Error> {
+    String::from_utf8(read_bytes(path)?).map_err(|_| Error::EILSEQ)
+}
+#[cfg(feature = "std")]
+pub fn read_to_string<P: AsRef<Path>>(path: P) -> Result<String, Error> {
+    read_to_string_bytes(bytes(path.as_ref()))
+}
+/// Read the entire contents of a file as UTF-8 in to a buffer.
+/// 
+/// Returns `ERANGE` if the buffer is too small and `EILSEQ` if the contents are not valid UTF-8.
+pub fn read_to_str_into_bytes<P: AsRef<[u8]>>(path: P, buffer: &mut [u8]) -> Result<&str, Error> {
+    core::str::from_utf8(read_into_bytes(path, buffer)?).map_err(|_| Error::EILSEQ)
+}
+#[cfg(feature = "std")]
+pub fn read_to_str_into<P: AsRef<Path>>(path: P, buffer: &mut [u8]) -> Result<&str, Error> {
+    read_to_str_into_bytes(bytes(path.as_ref()), buffer)
+}
+/// Replace the contents of a file, creating it if it does not exist.
+/// 
+/// A new file is created with mode `0o666`, less the process umask. See `atomic_write_bytes` for a crash-safe alternative.
+pub fn write_bytes<P: AsRef<[u8]>>(path: P, buffer: &[u8]) -> Result<(), Error> {
+    let flags = open::Flags::CREATE | open::Flags::TRUNCATE | open::Flags::WRITE_ONLY | open::Flags::CLOSE_ON_EXEC;
+    let file = with_path(path.as_ref(), |path| unsafe { crate::open_unsafe(path, flags, open::Mode(0o666)) })?;
+    write_all(&file, buffer)
+}
+#[cfg(feature = "std")]
+pub fn write<P: AsRef<Path>>(path: P, buffer: &[u8]) -> Result<(), Error> {
+    write_bytes(bytes(path.as_ref()), buffer)
+}
+/// Write to the end of a file, creating it if it does not exist.
+/// 
+/// A new file is created with mode `0o666`, less the process umask.
+pub fn append_bytes<P: AsRef<[u8]>>(path: P, buffer: &[u8]) -> Result<(), Error> {
+    let flags = open::Flags::CREATE | open::Flags::APPEND | open::Flags::WRITE_ONLY | open::Flags::CLOSE_ON_EXEC;
+    let file = with_path(path.as_ref(), |path| unsafe { crate::open_unsafe(path, flags, open::Mode(0o666)) })?;
+    write_all(&file, buffer)
+}
+#[cfg(feature = "std")]
+pub fn append<P: AsRef<Path>>(path: P, buffer: &[u8]) -> Result<(), Error> {
+    append_bytes(bytes(path.as_ref()), buffer)
+}
+/// Create a directory and any missing parents.
+/// 
+/// Directories are created with mode `0o777`, less the process umask. It is not an error if the directory already exists.
+pub fn create_dir_all_bytes<P: AsRef<[u8]>>(path: P) -> Result<(), Error> {
+    let path = path.as_ref();
+    if path.is_empty() {
+        return Ok(())
+    }
+    let mkdir = || with_path(path, |path| unsafe { crate::mkdirat_unsafe(Fd::cwd, path, open::Mode(0o777)) });
+    let exists = || match metadata_bytes(path)?.file_type() {
+        FileType::DIRECTORY => Ok(()),
+        _ => Err(Error::EEXIST)
+    };
+    match mkdir() {
+        Ok(()) => return Ok(()),
+        Err(Error::EEXIST) => return exists(),
+        Err(Error::ENOENT) => create_dir_all_bytes(parent(path).ok_or(Error::ENOENT)?)?,
+        Err(e) => return Err(e)
+    }
+    // Another process may have created the directory in the meantime
+    match mkdir() {
+        Err(Error::EEXIST) => exists(),
+        result => result
+    }
+}
+#[cfg(feature = "std")]
+pub fn create_dir_all<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    create_dir_all_bytes(bytes(path.as_ref()))
+}
+/// Remove a file. Directories are not removed.
+pub fn remove_file_bytes<P: AsRef<[u8]>>(path: P) -> Result<(), Error> {
+    with_path(path.as_ref(), |path| unsafe { crate::unlink_unsafe(path) })
+}
+#[cfg(feature = "std")]
+pub fn remove_file<P: AsRef<Path>>(path: P) -> Result<(), Error> {
+    remove_file_bytes(bytes(path.as_ref()))
+}
+/// Get the metadata of a file, following symlinks.
+pub fn metadata_bytes<P: AsRef<[u8]>>(path: P) -> Result<Stat, Error> {
+    with_path(path.as_ref(), |path| unsafe { crate::stat_unsafe(path) })
+}
+#[cfg(feature = "std")]
+pub fn metadata<P: AsRef<Path>>(path: P) -> Result<Stat, Error> {
+    metadata_bytes(bytes(path.as_ref()))
+}
+/// Get the metadata of a file without following a final symlink.
+pub fn symlink_metadata_bytes<P: AsRef<[u8]>>(path: P) -> Result<Stat, Error> {
+    with_path(path.as_ref(), |path| unsafe { crate::lstat_unsafe(path) })
+}
+#[cfg(feature = "std")]
+pub fn symlink_metadata<P: AsRef<Path>>(path: P) -> Result<Stat, Error> {
+    symlink_metadata_bytes(bytes(path.as_ref()))
+}
+/// Check if a path exists, following symlinks.
+/// 
+/// Only `ENOENT` is treated as not existing; other errors, such as `EACCES`, are returned.
+pub fn exists_bytes<P: AsRef<[u8]>>(path: P) -> Result<bool, Error> {
+    match metadata_bytes(path) {
+        Ok(_) => Ok(true),
+        Err(Error::ENOENT) => Ok(false),
+        Err(e) => Err(e)
+    }
+}
+#[cfg(feature = "std")]
+pub fn exists<P: AsRef<Path>>(path: P) -> Result<bool, Error> {
+    exists_bytes(bytes(path.as_ref()))
+}
+/// Resolve a path to an absolute path with all symlinks, `.` and `..` components removed.
+/// 
+/// Requires `/proc` to be mounted.
+#[cfg(any(feature = "std", feature = "alloc"))]
+pub fn canonicalize_bytes<P: AsRef<[u8]>>(path: P) -> Result<Vec<u8>, Error> {
+    let mut buffer = [0; PATH_MAX];
+    canonicalize_into_bytes(path, &mut buffer).map(<[u8]>::to_vec)
+}
+#[cfg(feature = "std")]
+pub fn canonicalize<P: AsRef<Path>>(path: P) -> Result<std::path::PathBuf, Error> {
+    let mut buffer = [0; PATH_MAX];
+    canonicalize_into(path, &mut buffer).map(Path::to_path_buf)
+}
+/// Resolve a path to an absolute path in to a buffer.
+/// 
+/// Returns `ERANGE` if the buffer is too small. Requires `/proc` to be mounted.
+pub fn canonicalize_into_bytes<P: AsRef<[u8]>>(path: P, buffer: &mut [u8]) -> Result<&[u8], Error> {
+    let file = with_path(path.as_ref(), |path| unsafe { crate::open_path_unsafe(path, open::Flags::CLOSE_ON_EXEC) })?;
+    let fd_path = proc_fd_path(file.raw());
+    let len = buffer.len();
+    let target = unsafe { crate::readlinkat_unsafe(Fd::cwd, fd_path.as_ptr(), buffer)? };
+    // The target may have been truncated
+    if target.len() == len {
+        return Err(Error::ERANGE)
+    }
+    Ok(target)
+}
+#[cfg(feature = "std")]
+pub fn canonicalize_into<P: AsRef<Path>>(path: P, buffer: &mut [u8]) -> Result<&Path, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    canonicalize_into_bytes(bytes(path.as_ref()), buffer).map(|path| Path::new(std::ffi::OsStr::from_bytes(path)))
+}
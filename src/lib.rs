@@ -6,7 +6,19 @@ compile_error!("syslib only supports Linux");
 mod sys;
 pub use sys::*;
 
+pub mod acl;
+#[cfg(feature = "alloc")]
+pub mod alloc;
+pub mod channel;
+pub mod exec;
+pub mod fs;
+pub mod mountinfo;
+pub mod remote;
+pub mod ring;
+pub mod secret;
 pub mod sock;
+#[cfg(feature = "std")]
+pub mod walk;
 
 pub const NUMBER_BITS: u32 = 8;
 pub const NUMBER_SHIFT: u32 = 0;
@@ -129,10 +141,11 @@ macro_rules! c_flags {
         impl ::core::fmt::Debug for $name {
             fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
                 #[derive(Debug)]
+                #[allow(dead_code)]
                 struct Unknown($ty);
                 $(
                     #[derive(Debug)]
-                    #[allow(non_camel_case_types)]
+                    #[allow(non_camel_case_types, clippy::upper_case_acronyms)]
                     struct $item;
                 )*
                 let mut list = f.debug_tuple(stringify!($name));
@@ -183,7 +196,7 @@ macro_rules! enumeration {
             fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
                 match *self {
                     $(
-                        Self::$item => ::core::write!(f, "{}({})", ::core::stringify!($item), $value),
+                        Self::$item => ::core::write!(f, "{}({})", ::core::stringify!($item), self.0),
                     )*
                     e => ::core::write!(f, "UNKNOWN({})", e.0)
                 }
@@ -201,9 +214,9 @@ macro_rules! enumeration {
             }
         }
 
-        impl ::core::convert::Into<$ty> for $ident {
-            fn into(self) -> $ty {
-                self.0
+        impl ::core::convert::From<$ident> for $ty {
+            fn from(value: $ident) -> Self {
+                value.0
             }
         }
         impl ::core::convert::From<$ty> for $ident {
@@ -221,4 +234,908 @@ mod test {
         use crate::sys::*;
         write(Fd::stdout, format!("{:#?}", stat("/dev/null").unwrap()).as_bytes()).unwrap();
     }
-}
\ No newline at end of file
+    #[test]
+    fn test_metadata() {
+        use crate::sys::*;
+        let file = memfd_create("test_metadata", MemfdFlags::NONE).unwrap();
+        file.truncate(4096).unwrap();
+        assert_eq!(fstat(&file).unwrap().size, 4096);
+        file.allocate(FallocateMode::KEEP_SIZE, 0, 8192).unwrap();
+        assert_eq!(fstat(&file).unwrap().size, 4096);
+        file.chmod(open::Mode(0o640)).unwrap();
+        assert_eq!(fstat(&file).unwrap().mode & 0o7777, 0o640);
+        file.set_times(Timespec::new(1, 2), Timespec::OMIT).unwrap();
+        let stat = fstat(&file).unwrap();
+        assert_eq!((stat.access_time, stat.access_time_nsec), (1, 2));
+    }
+    #[test]
+    fn test_statfs() {
+        use crate::sys::*;
+        let proc = statfs("/proc").unwrap();
+        assert_eq!(proc.file_system, statfs::FsMagic::PROC);
+        let memfd = memfd_create("test_statfs", MemfdFlags::NONE).unwrap();
+        assert_eq!(fstatfs(&memfd).unwrap().file_system, statfs::FsMagic::TMPFS);
+    }
+    /// Run `f` in a forked child in new user and mount namespaces, as root of the user namespace, and assert that it
+    /// succeeds.
+    ///
+    /// Other test threads may hold locks when the process forks, so the child must not allocate or panic. It reports
+    /// failure through its exit status instead.
+    fn in_namespaces<F: FnOnce() -> Result<(), crate::Error>>(f: F) {
+        use crate::sys::*;
+        use crate::clone::{Flags, Fork, WaitFlags};
+        /// Write a single-line identity mapping from 0 to `id` to a file in `/proc/self`.
+        fn map(path: &core::ffi::CStr, id: u32) -> Result<(), Error> {
+            let mut line = *b"0 0000000000 1\n";
+            let mut n = id;
+            for digit in line[2..12].iter_mut().rev() {
+                *digit = b'0' + (n % 10) as u8;
+                n /= 10;
+            }
+            let file = unsafe { open_unsafe(path.as_ptr().cast(), open::Flags::WRITE_ONLY | open::Flags::CLOSE_ON_EXEC, open::Mode::NONE)? };
+            crate::fs::write_all(&file, &line)
+        }
+        let (uid, gid) = (getuid(), getgid());
+        let child = || -> Result<(), Error> {
+            unshare(Flags::NEW_USER | Flags::NEW_MOUNT)?;
+            map(c"/proc/self/uid_map", uid)?;
+            let setgroups = unsafe { open_unsafe(c"/proc/self/setgroups".as_ptr().cast(), open::Flags::WRITE_ONLY | open::Flags::CLOSE_ON_EXEC, open::Mode::NONE)? };
+            crate::fs::write_all(&setgroups, b"deny")?;
+            map(c"/proc/self/gid_map", gid)?;
+            f()
+        };
+        let pid = match unsafe { fork() }.unwrap() {
+            Fork::Child => exit(child().map_or_else(|e| u32::from(e) as i32, |()| 0)),
+            Fork::Parent { pid, .. } => pid
+        };
+        let code = wait4(pid as i32, WaitFlags::NONE).unwrap().1.exit_code().unwrap();
+        assert_eq!(code, 0, "{:?}", Error::from(code as u32));
+    }
+    /// Fail with `EBADMSG` unless `condition` holds, for checks in a child that cannot panic.
+    fn ensure(condition: bool) -> Result<(), crate::Error> {
+        if condition {
+            Ok(())
+        } else {
+            Err(crate::Error::EBADMSG)
+        }
+    }
+    #[test]
+    fn test_fsmount() {
+        use crate::sys::*;
+        use crate::mount::{Attributes, Config, FsmountFlags, FsopenFlags, MountAttr};
+        // Mounting needs CAP_SYS_ADMIN in the user namespace owning the mount namespace
+        in_namespaces(|| {
+            let context = unsafe { fsopen_unsafe(c"tmpfs".as_ptr().cast(), FsopenFlags::CLOSE_ON_EXEC)? };
+            ensure(context.config(Config::SetString(c"syslib", c"")) == Err(Error::EINVAL))?;
+            // The wording of the message varies between kernels
+            let mut message = [0; 256];
+            let message = context.message(&mut message)?;
+            ensure(message.starts_with(b"e ") && message.windows(6).any(|name| name == b"syslib"))?;
+            context.config(Config::SetString(c"size", c"1M"))?;
+            context.config(Config::Create)?;
+            let mount = context.mount(FsmountFlags::CLOSE_ON_EXEC, Attributes::NO_EXEC)?;
+            ensure(fstatfs(&mount)?.file_system == statfs::FsMagic::TMPFS)?;
+            let flags = open::Flags::CREATE | open::Flags::WRITE_ONLY | open::Flags::CLOSE_ON_EXEC;
+            unsafe { openat_unsafe(&mount, c"file".as_ptr().cast(), flags, open::Mode(0o600))? };
+            unsafe { mount_setattr_unsafe(&mount, c"".as_ptr().cast(), AtFlags::EMPTY_PATH, &MountAttr::new().set(Attributes::READ_ONLY))? };
+            let other = unsafe { openat_unsafe(&mount, c"other".as_ptr().cast(), flags, open::Mode(0o600)) };
+            ensure(matches!(other, Err(Error::EROFS)))
+        });
+    }
+    #[test]
+    fn test_atomic_write() {
+        use crate::sys::*;
+        let path = std::env::temp_dir().join(format!("syslib_test_atomic_write_{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        let dir = open(&path, open::Flags::DIRECTORY | open::Flags::READ_ONLY, open::Mode::NONE).unwrap();
+        crate::fs::atomic_write(&dir, "config", b"first").unwrap();
+        crate::fs::atomic_write(&dir, "config", b"second").unwrap();
+        assert_eq!(std::fs::read(path.join("config")).unwrap(), b"second");
+        assert_eq!(std::fs::read_dir(&path).unwrap().count(), 1);
+        assert_eq!(crate::fs::atomic_write(&dir, "../config", b"").unwrap_err(), Error::EINVAL);
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+    #[test]
+    fn test_renameat2() {
+        use crate::sys::*;
+        let path = std::env::temp_dir().join(format!("syslib_test_renameat2_{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        std::fs::write(path.join("blue"), b"blue").unwrap();
+        std::fs::write(path.join("green"), b"green").unwrap();
+        let dir = open(&path, open::Flags::DIRECTORY | open::Flags::READ_ONLY, open::Mode::NONE).unwrap();
+        assert_eq!(renameat2(&dir, "blue", &dir, "green", RenameFlags::NO_REPLACE).unwrap_err(), Error::EEXIST);
+        assert_eq!(renameat2(&dir, "blue", &dir, "green", RenameFlags::NO_REPLACE | RenameFlags::EXCHANGE).unwrap_err(), Error::EINVAL);
+        renameat2(&dir, "blue", &dir, "green", RenameFlags::EXCHANGE).unwrap();
+        assert_eq!(std::fs::read(path.join("blue")).unwrap(), b"green");
+        assert_eq!(std::fs::read(path.join("green")).unwrap(), b"blue");
+        // Moving a directory beneath itself is refused by the kernel, not the file system
+        std::fs::create_dir_all(path.join("tree/sub")).unwrap();
+        assert_eq!(renameat2(&dir, "tree", &dir, "tree/sub/tree", RenameFlags::NO_REPLACE).unwrap_err(), Error::EINVAL);
+        assert_eq!(renameat2(&dir, "tree", &dir, "tree/sub", RenameFlags::EXCHANGE).unwrap_err(), Error::EINVAL);
+        assert_eq!(renameat2(&dir, "tree/sub", &dir, "tree", RenameFlags::EXCHANGE).unwrap_err(), Error::EINVAL);
+        std::fs::remove_dir_all(&path).unwrap();
+
+        // ramfs supports `NO_REPLACE` and `EXCHANGE`, but not `WHITEOUT`, which also needs CAP_MKNOD
+        use crate::mount::{Attributes, Config, FsmountFlags, FsopenFlags};
+        let context = match fsopen("ramfs", FsopenFlags::CLOSE_ON_EXEC) {
+            Ok(context) => context,
+            Err(Error::EPERM) => return,
+            Err(e) => panic!("{e:?}")
+        };
+        context.config(Config::Create).unwrap();
+        let mount = context.mount(FsmountFlags::CLOSE_ON_EXEC, Attributes::NONE).unwrap();
+        openat(&mount, "blue", open::Flags::CREATE | open::Flags::WRITE_ONLY, open::Mode(0o600)).unwrap();
+        assert_eq!(renameat2(&mount, "blue", &mount, "green", RenameFlags::WHITEOUT).unwrap_err(), Error::EOPNOTSUPP);
+        renameat2(&mount, "blue", &mount, "green", RenameFlags::NO_REPLACE).unwrap();
+    }
+    #[test]
+    fn test_xattr() {
+        use crate::sys::*;
+        use crate::acl::{self, Entry, Permissions, Tag};
+        let path = std::env::temp_dir().join(format!("syslib_test_xattr_{}", std::process::id()));
+        let file = open(&path, open::Flags::CREATE | open::Flags::READ_WRITE, open::Mode(0o600)).unwrap();
+        fsetxattr(&file, "user.syslib", b"value", XattrFlags::CREATE).unwrap();
+        assert_eq!(fsetxattr(&file, "user.syslib", b"value", XattrFlags::CREATE).unwrap_err(), Error::EEXIST);
+        let mut value = [0; 16];
+        assert_eq!(getxattr(&path, "user.syslib", &mut []).unwrap(), 5);
+        let len = fgetxattr(&file, "user.syslib", &mut value).unwrap();
+        assert_eq!(&value[..len], b"value");
+        let mut list = [0; 256];
+        let len = flistxattr(&file, &mut list).unwrap();
+        assert!(XattrNames::new(&list[..len]).any(|name| name == b"user.syslib"));
+        removexattr(&path, "user.syslib").unwrap();
+        assert_eq!(fgetxattr(&file, "user.syslib", &mut value).unwrap_err(), Error::ENODATA);
+
+        let entries = [
+            Entry::new(Tag::UserObject, Permissions::READ | Permissions::WRITE),
+            Entry::new(Tag::User(1000), Permissions::READ),
+            Entry::new(Tag::GroupObject, Permissions::READ),
+            Entry::new(Tag::Mask, Permissions::READ),
+            Entry::new(Tag::Other, Permissions::NONE)
+        ];
+        let mut buffer = [0; acl::encoded_len(5)];
+        assert_eq!(acl::encode(&entries, &mut buffer[..8]).unwrap_err(), Error::ERANGE);
+        let len = acl::encode(&entries, &mut buffer).unwrap();
+        let decoded = acl::Acl::decode(&buffer[..len]).unwrap();
+        assert!(decoded.entries().eq(entries.iter().copied()));
+        assert_eq!(acl::Acl::decode(&buffer[..len - 1]).unwrap_err(), Error::EINVAL);
+        unlink(&path).unwrap();
+    }
+    #[test]
+    fn test_inotify() {
+        use crate::sys::*;
+        use crate::inotify::{Events, Notification, WatchMask};
+        let path = std::env::temp_dir().join(format!("syslib_test_inotify_{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        let inotify = inotify_init(inotify::Flags::CLOSE_ON_EXEC | inotify::Flags::NON_BLOCKING).unwrap();
+        let watch = inotify_add_watch(&inotify, &path, WatchMask::CREATE | WatchMask::MOVE).unwrap();
+        std::fs::write(path.join("a"), b"").unwrap();
+        std::fs::rename(path.join("a"), path.join("b")).unwrap();
+        let mut buffer = [0; 1024];
+        let events = inotify.read(&mut buffer).unwrap();
+        let mut notifications = Events::new(events).paired();
+        match notifications.next() {
+            Some(Notification::Event(event)) => {
+                assert_eq!((event.wd, event.mask, event.name), (watch, WatchMask::CREATE, Some(&b"a"[..])));
+            },
+            n => panic!("unexpected notification {n:?}")
+        }
+        match notifications.next() {
+            Some(Notification::Moved { from, to }) => assert_eq!((from.name, to.name), (Some(&b"a"[..]), Some(&b"b"[..]))),
+            n => panic!("unexpected notification {n:?}")
+        }
+        assert!(notifications.next().is_none());
+        inotify_rm_watch(&inotify, watch).unwrap();
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+    #[test]
+    fn test_walk() {
+        use crate::sys::*;
+        use crate::walk::{self, Control, Visit, Walk};
+        use std::os::unix::fs::symlink;
+        let path = std::env::temp_dir().join(format!("syslib_test_walk_{}", std::process::id()));
+        std::fs::create_dir_all(path.join("from/a/b")).unwrap();
+        std::fs::create_dir_all(path.join("to")).unwrap();
+        std::fs::write(path.join("outside"), b"keep").unwrap();
+        std::fs::write(path.join("from/a/b/file"), b"data").unwrap();
+        symlink(path.join("outside"), path.join("from/a/link")).unwrap();
+        symlink("..", path.join("from/a/b/parent")).unwrap();
+        let dir = open(&path, open::Flags::DIRECTORY | open::Flags::READ_ONLY, open::Mode::NONE).unwrap();
+        let from = openat(&dir, "from", open::Flags::DIRECTORY | open::Flags::READ_ONLY, open::Mode::NONE).unwrap();
+        let to = openat(&dir, "to", open::Flags::DIRECTORY | open::Flags::READ_ONLY, open::Mode::NONE).unwrap();
+
+        let mut loops = Vec::new();
+        Walk::new().follow_symlinks(true).run(&from, |visit| {
+            if let Visit::Loop(entry) = visit {
+                loops.push(entry.path.to_owned());
+            }
+            Ok(Control::Continue)
+        }).unwrap();
+        assert_eq!(loops, [std::path::Path::new("a/b/parent")]);
+
+        walk::copy_tree(&from, &to).unwrap();
+        assert_eq!(std::fs::read(path.join("to/a/b/file")).unwrap(), b"data");
+        assert_eq!(std::fs::read_link(path.join("to/a/link")).unwrap(), path.join("outside"));
+
+        walk::remove_dir_all(&dir, "from").unwrap();
+        walk::remove_dir_all(&dir, "to").unwrap();
+        assert!(!path.join("from").exists());
+        assert_eq!(std::fs::read(path.join("outside")).unwrap(), b"keep");
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_mountinfo() {
+        use crate::sys::*;
+        use crate::mountinfo::{self, MountInfo, Mounts, OptionalField};
+        let line = b"36 35 98:0 /mnt1 /mnt/my\\040dir rw,noatime master:1 shared:7 - ext3 /dev/root rw,errors=continue\n";
+        let info = Mounts::new(line).next().unwrap().unwrap();
+        assert_eq!((info.id, info.parent_id), (36, 35));
+        assert_eq!((info.device.major(), info.device.minor()), (98, 0));
+        assert!(info.mount_point == *b"/mnt/my dir");
+        assert_eq!(info.mount_point.decode(&mut [0; 16]).unwrap(), b"/mnt/my dir");
+        assert_eq!(info.mount_point.decode(&mut [0; 4]), Err(Error::ERANGE));
+        assert!(info.optional_fields().eq([OptionalField::Master(1), OptionalField::Shared(7)]));
+        assert!(info.fs_type == *b"ext3");
+        assert!(mountinfo::options(info.super_options).eq([&b"rw"[..], b"errors=continue"]));
+
+        let info = MountInfo::parse(b"1 0 0:1 / / rw - rootfs rootfs rw").unwrap();
+        assert_eq!(info.optional_fields().count(), 0);
+        assert!(MountInfo::parse(b"1 0 0:1 / / rw").is_err());
+
+        let contents = std::fs::read("/proc/self/mountinfo").unwrap();
+        assert!(Mounts::new(&contents).map(Result::unwrap).any(|info| info.mount_point == *b"/"));
+    }
+    #[test]
+    fn test_mount() {
+        use crate::sys::*;
+        use crate::mount::{MountFlags, UnmountFlags};
+        use std::os::unix::prelude::OsStrExt;
+        let path = std::env::temp_dir().join(format!("syslib_test_mount_{}", std::process::id()));
+        std::fs::create_dir_all(&path).unwrap();
+        let target = std::ffi::CString::new(path.as_os_str().as_bytes()).unwrap();
+        let target = target.as_ptr().cast();
+        in_namespaces(|| unsafe {
+            let underlying = stat_unsafe(target)?;
+            mount_unsafe(c"none".as_ptr().cast(), target, c"tmpfs".as_ptr().cast(), MountFlags::NO_SETUID | MountFlags::NO_DEVICE, c"size=1M".as_ptr().cast())?;
+            let stat = statfs_unsafe(target)?;
+            ensure(stat.file_system == statfs::FsMagic::TMPFS && stat_unsafe(target)?.device_id != underlying.device_id)?;
+            ensure(umount2_unsafe(target, UnmountFlags::NO_FOLLOW | UnmountFlags::EXPIRE) == Err(Error::EAGAIN))?;
+            umount2_unsafe(target, UnmountFlags::NO_FOLLOW)?;
+            ensure(stat_unsafe(target)?.device_id == underlying.device_id)?;
+            ensure(umount2_unsafe(target, UnmountFlags::NONE) == Err(Error::EINVAL))
+        });
+        std::fs::remove_dir(&path).unwrap();
+    }
+
+    #[test]
+    fn test_fs() {
+        use crate::sys::*;
+        use crate::fs;
+        let path = format!("{}/syslib_test_fs_{}", std::env::temp_dir().display(), std::process::id());
+        let file = format!("{path}/a/b/file");
+        fs::create_dir_all(format!("{path}/a/b")).unwrap();
+        fs::create_dir_all(format!("{path}/a//b/")).unwrap();
+        assert!(!fs::exists(&file).unwrap());
+        fs::write(&file, b"hello").unwrap();
+        fs::append(&file, b", world").unwrap();
+        assert_eq!(fs::read(std::path::Path::new(&file)).unwrap(), b"hello, world");
+        assert_eq!(fs::read_to_str_into(&file, &mut [0; 12]).unwrap(), "hello, world");
+        assert_eq!(fs::read_into(&file, &mut [0; 11]), Err(Error::ERANGE));
+        assert_eq!(fs::create_dir_all(&file), Err(Error::EEXIST));
+        assert_eq!(fs::create_dir_all(format!("{file}/c")), Err(Error::ENOTDIR));
+        assert!(!fs::read_to_string("/proc/self/status").unwrap().is_empty());
+        assert_eq!(fs::read_into("a\0b", &mut []), Err(Error::EINVAL));
+        assert_eq!(fs::read_bytes(file.as_bytes()).unwrap(), b"hello, world");
+        assert_eq!(fs::read_to_str_into_bytes(file.as_bytes(), &mut [0; 12]).unwrap(), "hello, world");
+        assert!(fs::exists_bytes(b"/proc/self").unwrap());
+
+        std::os::unix::fs::symlink("b/file", format!("{path}/a/link")).unwrap();
+        assert_eq!(fs::metadata(format!("{path}/a/link")).unwrap().file_type(), FileType::REGULAR);
+        assert_eq!(fs::symlink_metadata(format!("{path}/a/link")).unwrap().file_type(), FileType::SYMLINK);
+        assert_eq!(fs::canonicalize(format!("{path}/a/b/../link")).unwrap(), std::fs::canonicalize(&file).unwrap());
+        let canonical = std::fs::canonicalize(&file).unwrap();
+        let mut buffer = [0; fs::PATH_MAX];
+        assert_eq!(fs::canonicalize_into(format!("{path}/a/link"), &mut buffer).unwrap(), canonical);
+        assert_eq!(fs::canonicalize_bytes(format!("{path}/a/link")).unwrap(), canonical.as_os_str().as_encoded_bytes());
+        assert_eq!(fs::canonicalize_into(format!("{path}/a/link"), &mut [0; 8]), Err(Error::ERANGE));
+
+        fs::remove_file(format!("{path}/a/link")).unwrap();
+        fs::remove_file(&file).unwrap();
+        assert_eq!(fs::remove_file(&file), Err(Error::ENOENT));
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_open_options() {
+        use crate::sys::*;
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("syslib_test_open_options_{}", std::process::id()));
+        std::fs::create_dir(&path).unwrap();
+        let file = path.join("file");
+        assert_eq!(OpenOptions::new().open(&file).unwrap_err(), Error::EINVAL);
+        assert_eq!(OpenOptions::new().read(true).truncate(true).flags().unwrap_err(), Error::EINVAL);
+        assert_eq!(OpenOptions::new().read(true).create(true).flags().unwrap_err(), Error::EINVAL);
+        assert_eq!(OpenOptions::new().read(true).temporary(true).flags().unwrap_err(), Error::EINVAL);
+        assert_eq!(OpenOptions::new().write(true).custom_flags(open::Flags::TRUNCATE).flags().unwrap_err(), Error::EINVAL);
+        assert_eq!(
+            OpenOptions::new().read(true).append(true).create_new(true).flags().unwrap(),
+            open::Flags::READ_WRITE | open::Flags::APPEND | open::Flags::CREATE | open::Flags::EXCLUSIVE | open::Flags::CLOSE_ON_EXEC
+        );
+
+        let options = OpenOptions::new().write(true).create_new(true).mode(open::Mode(0o600));
+        options.open(&file).unwrap().write(b"hello").unwrap();
+        assert_eq!(options.open(&file).unwrap_err(), Error::EEXIST);
+        assert_eq!(std::fs::metadata(&file).unwrap().permissions().mode() & 0o777, 0o600);
+        OpenOptions::new().write(true).truncate(true).open(&file).unwrap();
+        assert_eq!(std::fs::read(&file).unwrap(), b"");
+
+        let dir = open_path(&path, open::Flags::DIRECTORY | open::Flags::CLOSE_ON_EXEC).unwrap();
+        assert_eq!(dir.stat().unwrap().file_type(), FileType::DIRECTORY);
+        assert_eq!(open_path(&path, open::Flags::READ_WRITE).unwrap_err(), Error::EINVAL);
+        assert_eq!(dir.stat_at(c"file", AtFlags::NONE).unwrap().size, 0);
+        let file = dir.open_path_at(c"file", open::Flags::CLOSE_ON_EXEC).unwrap();
+        assert_eq!(file.stat_at(c"", AtFlags::EMPTY_PATH).unwrap().file_type(), FileType::REGULAR);
+        assert_eq!(file.open_path_at(c"", open::Flags::NO_FOLLOW | open::Flags::WRITE_ONLY).unwrap_err(), Error::EINVAL);
+        assert_eq!(file.chdir().unwrap_err(), Error::ENOTDIR);
+        let options = OpenOptions::new().write(true).temporary(true);
+        match dir.open_at(c".", options.flags().unwrap(), open::Mode(0o600)) {
+            Ok(temporary) => assert_eq!(temporary.write(b"unnamed").unwrap(), 7),
+            Err(e) => assert_eq!(e, Error::EOPNOTSUPP)
+        }
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_faccessat2() {
+        use crate::sys::*;
+        let path = std::env::temp_dir().join(format!("syslib_test_faccessat2_{}", std::process::id()));
+        std::fs::create_dir(&path).unwrap();
+        std::fs::write(path.join("file"), b"").unwrap();
+        std::os::unix::fs::symlink("file", path.join("link")).unwrap();
+        std::os::unix::fs::symlink("missing", path.join("dangling")).unwrap();
+        let dir = open(&path, open::Flags::DIRECTORY | open::Flags::READ_ONLY, open::Mode::NONE).unwrap();
+        let file = openat(&dir, "file", open::Flags::READ_ONLY, open::Mode::NONE).unwrap();
+        file.chmod(open::Mode(0o644)).unwrap();
+
+        let emulated = |dir: &Fd, path: &std::ffi::CStr, mode, flags| unsafe { faccessat_emulated(dir, path.as_ptr() as *const u8, mode, flags) };
+        for (fd, name, mode, flags, expected) in [
+            (dir.fd(), c"file", AccessMode::READ | AccessMode::WRITE, AtFlags::EFFECTIVE_ACCESS, Ok(())),
+            (dir.fd(), c"file", AccessMode::EXECUTE, AtFlags::NONE, Err(Error::EACCES)),
+            (dir.fd(), c"link", AccessMode::EXECUTE, AtFlags::SYMLINK_NO_FOLLOW, Err(Error::ENOSYS)),
+            (dir.fd(), c"dangling", AccessMode::EXISTS, AtFlags::SYMLINK_NO_FOLLOW, Ok(())),
+            (dir.fd(), c"dangling", AccessMode::EXISTS, AtFlags::NONE, Err(Error::ENOENT)),
+            (file.fd(), c"", AccessMode::READ, AtFlags::EMPTY_PATH, Ok(())),
+            (file.fd(), c"", AccessMode::EXECUTE, AtFlags::EMPTY_PATH, Err(Error::EACCES)),
+            (*Fd::cwd, c"", AccessMode::EXISTS, AtFlags::EMPTY_PATH, Ok(()))
+        ] {
+            assert_eq!(emulated(&fd, name, mode, flags), expected, "{name:?} {mode:?} {flags:?}");
+            if expected != Err(Error::ENOSYS) {
+                let native = unsafe { faccessat2_unsafe(fd, name.as_ptr() as *const u8, mode, flags) };
+                assert_eq!(native, expected, "{name:?} {mode:?} {flags:?}");
+            }
+        }
+        assert_eq!(faccessat2(&dir, "file", AccessMode::READ, AtFlags::RECURSIVE), Err(Error::EINVAL));
+        std::fs::remove_dir_all(&path).unwrap();
+    }
+
+    #[test]
+    fn test_seals() {
+        use crate::sys::*;
+        let unsealable = memfd_create("test_unsealable", MemfdFlags::CLOSE_ON_EXEC).unwrap();
+        assert_eq!(unsealable.seals(), Ok(Seals::SEAL));
+        assert_eq!(unsealable.add_seals(Seals::WRITE), Err(Error::EPERM));
+
+        let memfd = memfd_create("test_seals", MemfdFlags::CLOSE_ON_EXEC | MemfdFlags::ALLOW_SEALING).unwrap();
+        assert_eq!(memfd.seals(), Ok(Seals::NONE));
+        memfd.write(b"sealed").unwrap();
+        memfd.add_seals(Seals::SHRINK | Seals::GROW | Seals::WRITE).unwrap();
+        memfd.add_seals(Seals::SEAL).unwrap();
+        assert_eq!(memfd.seals(), Ok(Seals::SEAL | Seals::SHRINK | Seals::GROW | Seals::WRITE));
+        assert_eq!(memfd.write(b"!"), Err(Error::EPERM));
+        assert_eq!(memfd.truncate(0), Err(Error::EPERM));
+        assert_eq!(memfd.add_seals(Seals::EXEC), Err(Error::EPERM));
+        assert_eq!(fstat(&memfd).unwrap().size, 6);
+    }
+
+    #[test]
+    fn test_mapping() {
+        use crate::sys::*;
+        use crate::mmap::{Flags, Mapping, Protection};
+        let mut mapping = Mapping::anonymous(4096).unwrap();
+        assert!(mapping.iter().all(|&b| b == 0));
+        mapping.as_mut_slice().unwrap()[..5].copy_from_slice(b"hello");
+        mapping.resize(3 * 4096).unwrap();
+        assert_eq!(mapping.len(), 3 * 4096);
+        assert_eq!(&mapping[..5], b"hello");
+
+        let reserved = Mapping::anonymous(4 * 4096).unwrap();
+        let (address, _) = reserved.into_raw();
+        unsafe { mapping.resize_at(address, 4 * 4096).unwrap() };
+        assert_eq!(mapping.as_ptr(), address as *mut u8);
+        assert_eq!(&mapping[..5], b"hello");
+
+        mapping.protect(Protection::READ).unwrap();
+        assert_eq!(mapping.as_mut_slice().unwrap_err(), Error::EACCES);
+        mapping.protect(Protection::NONE).unwrap();
+        assert_eq!(mapping.as_slice().unwrap_err(), Error::EACCES);
+
+        let memfd = memfd_create("test_mapping", MemfdFlags::CLOSE_ON_EXEC).unwrap();
+        memfd.truncate(2 * 4096).unwrap();
+        let mut shared = unsafe { Mapping::from_file(&memfd, 4096..2 * 4096, Protection::READ | Protection::WRITE, Flags::SHARED).unwrap() };
+        shared.as_mut_slice().unwrap()[..5].copy_from_slice(b"world");
+        let whole = unsafe { Mapping::from_file(&memfd, 0..2 * 4096, Protection::READ, Flags::SHARED).unwrap() };
+        assert_eq!(&whole[4096..4096 + 5], b"world");
+        assert_eq!(unsafe { Mapping::from_file(&memfd, 1..4096, Protection::READ, Flags::SHARED) }.unwrap_err(), Error::EINVAL);
+    }
+
+    #[test]
+    fn test_mmap_anonymous() {
+        use crate::sys::*;
+        use crate::mmap::{Flags, Mapping, Protection, PAGE_SIZE};
+        let protection = Protection::READ | Protection::WRITE;
+        let address = mmap_anonymous(0, 2 * PAGE_SIZE, protection, Flags::PRIVATE | Flags::POPULATE).unwrap();
+        let mapping = unsafe { Mapping::from_raw(address, 2 * PAGE_SIZE, protection) };
+        assert!(mapping.iter().all(|&b| b == 0));
+        assert_eq!(mmap_anonymous(address as usize + PAGE_SIZE, PAGE_SIZE, protection, Flags::PRIVATE | Flags::FIXED_NO_REPLACE), Err(Error::EEXIST));
+        assert_eq!(mmap_anonymous(0, PAGE_SIZE, protection, Flags::NONE), Err(Error::EINVAL));
+        assert_eq!(mmap_anonymous(address as usize + PAGE_SIZE, PAGE_SIZE, protection, Flags::PRIVATE | Flags::FIXED), Err(Error::EINVAL));
+        let mut mapping = mapping;
+        mapping.as_mut_slice().unwrap()[PAGE_SIZE] = 1;
+        let second = unsafe { mmap_anonymous_unsafe(address as usize + PAGE_SIZE, PAGE_SIZE, protection, Flags::PRIVATE | Flags::FIXED).unwrap() };
+        assert_eq!(second as usize, address as usize + PAGE_SIZE);
+        assert_eq!(mapping[PAGE_SIZE], 0);
+
+        let memfd = memfd_create("test_mmap_anonymous", MemfdFlags::CLOSE_ON_EXEC).unwrap();
+        memfd.truncate(2 * PAGE_SIZE as u64).unwrap();
+        assert_eq!(mmap(0, PAGE_SIZE, Protection::READ, Flags::SHARED_VALIDATE, &memfd, PAGE_SIZE / 2), Err(Error::EINVAL));
+        assert_eq!(mmap(address as usize, PAGE_SIZE, Protection::READ, Flags::SHARED | Flags::FIXED, &memfd, 0), Err(Error::EINVAL));
+    }
+
+    #[test]
+    fn test_madvise() {
+        use crate::sys::*;
+        use crate::mmap::{Advice, LockFlags, Mapping, MsyncFlags, PAGE_SIZE};
+        let mut mapping = Mapping::anonymous(4 * PAGE_SIZE).unwrap();
+        let mut residency = [0xFF; 4];
+        assert_eq!(mapping.residency(&mut residency).unwrap(), [0; 4]);
+        assert_eq!(mapping.residency(&mut [0; 3]).unwrap_err(), Error::ERANGE);
+        mapping.as_mut_slice().unwrap()[PAGE_SIZE] = 1;
+        assert_eq!(mapping.residency(&mut residency).unwrap(), [0, 1, 0, 0]);
+        mapping.advise(Advice::DONT_NEED).unwrap();
+        assert_eq!(mapping[PAGE_SIZE], 0);
+        mapping.advise(Advice::POPULATE_WRITE).unwrap();
+        assert_eq!(mapping.residency(&mut residency).unwrap(), [1; 4]);
+        mapping.lock(LockFlags::ON_FAULT).unwrap();
+        mapping.unlock().unwrap();
+
+        let pidfd = pidfd_open(getpid(), clone::PidfdFlags::NONE).unwrap();
+        let range = RemoteIoVec::new(mapping.as_ptr() as usize, mapping.len());
+        assert_eq!(process_madvise(&pidfd, &[range], Advice::COLD), Ok(mapping.len()));
+        assert_eq!(process_madvise(&pidfd, &[range], Advice::DONT_NEED), Err(Error::EINVAL));
+
+        let memfd = memfd_create("test_madvise", MemfdFlags::CLOSE_ON_EXEC).unwrap();
+        memfd.truncate(PAGE_SIZE as u64).unwrap();
+        let shared = unsafe { Mapping::from_file(&memfd, 0..PAGE_SIZE, mmap::Protection::READ, mmap::Flags::SHARED).unwrap() };
+        shared.sync(MsyncFlags::SYNC).unwrap();
+        assert_eq!(shared.sync(MsyncFlags::SYNC | MsyncFlags::ASYNC), Err(Error::EINVAL));
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        use crate::sys::*;
+        use crate::ring::RingBuffer;
+        assert_eq!(RingBuffer::new(100).unwrap_err(), Error::EINVAL);
+        let mut ring = RingBuffer::new(mmap::PAGE_SIZE).unwrap();
+        let capacity = ring.capacity();
+        ring.writable()[..capacity - 3].fill(b'a');
+        ring.commit(capacity - 3);
+        ring.consume(capacity - 3);
+        assert!(ring.is_empty());
+
+        // The write wraps around the end of the data pages but is still contiguous
+        ring.writable()[..6].copy_from_slice(b"wraps!");
+        ring.commit(6);
+        assert_eq!(ring.readable(), b"wraps!");
+        assert_eq!(ring.writable().len(), capacity - 6);
+
+        let fd = fcntl(ring.file(), Fcntl::DupFd(Fd::from_raw(0))).unwrap();
+        let mut peer = unsafe { RingBuffer::from_file(Fd::from_raw(fd).owned()).unwrap() };
+        assert_eq!(peer.readable(), b"wraps!");
+        peer.consume(5);
+        assert_eq!(ring.readable(), b"!");
+        assert_eq!(ring.file().truncate(0), Err(Error::EPERM));
+
+        let unsealed = memfd_create("test_ring_buffer", MemfdFlags::CLOSE_ON_EXEC).unwrap();
+        unsealed.truncate(2 * mmap::PAGE_SIZE as u64).unwrap();
+        assert_eq!(unsafe { RingBuffer::from_file(unsealed) }.unwrap_err(), Error::EINVAL);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn test_allocator() {
+        use crate::alloc::Allocator;
+        use core::alloc::{GlobalAlloc, Layout};
+        let allocator = Allocator::new();
+        unsafe {
+            let small = Layout::from_size_align(24, 8).unwrap();
+            let a = allocator.alloc(small);
+            let b = allocator.alloc(small);
+            assert!(!a.is_null() && !b.is_null() && a != b);
+            a.write_bytes(0xAA, 24);
+            allocator.dealloc(a, small);
+            let c = allocator.alloc_zeroed(small);
+            assert_eq!(c, a);
+            assert_eq!(core::slice::from_raw_parts(c, 24), [0; 24]);
+
+            let aligned = Layout::from_size_align(100, 512).unwrap();
+            assert_eq!(allocator.alloc(aligned) as usize % 512, 0);
+            let huge_align = Layout::from_size_align(3 * 4096, 1 << 20).unwrap();
+            let d = allocator.alloc_zeroed(huge_align);
+            assert_eq!(d as usize % (1 << 20), 0);
+            allocator.dealloc(d, huge_align);
+
+            // Growing within a size class keeps the object, and large objects are remapped
+            let e = allocator.alloc(Layout::from_size_align(20, 1).unwrap());
+            e.copy_from(b"growing".as_ptr(), 7);
+            assert_eq!(allocator.realloc(e, Layout::from_size_align(20, 1).unwrap(), 30), e);
+            let large = allocator.realloc(e, Layout::from_size_align(30, 1).unwrap(), 10_000);
+            assert_eq!(core::slice::from_raw_parts(large, 7), b"growing");
+            let larger = allocator.realloc(large, Layout::from_size_align(10_000, 1).unwrap(), 1 << 20);
+            assert_eq!(core::slice::from_raw_parts(larger, 7), b"growing");
+            allocator.dealloc(larger, Layout::from_size_align(1 << 20, 1).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_userfaultfd() {
+        use crate::sys::*;
+        use crate::mmap::Mapping;
+        use crate::userfaultfd::{CopyMode, Event, Events, Features, Flags, Ioctls, PagefaultFlags, Range, RegisterMode, ZeropageMode};
+        let uffd = match userfaultfd(Flags::USER_MODE_ONLY | Flags::CLOSE_ON_EXEC) {
+            // Disabled by the kernel or `vm.unprivileged_userfaultfd`
+            Err(Error::ENOSYS | Error::EPERM) => return,
+            uffd => uffd.unwrap()
+        };
+        let api = uffd.api(Features::NONE).unwrap();
+        assert!(api.ioctls.all(Ioctls::REGISTER | Ioctls::UNREGISTER | Ioctls::API));
+
+        let mapping = Mapping::anonymous(2 * 4096).unwrap();
+        let start = mapping.as_ptr() as usize;
+        // Safety: the mapping is only read after each fault has been resolved
+        let ioctls = unsafe { uffd.register(Range::new(start, 2 * 4096), RegisterMode::MISSING).unwrap() };
+        assert!(ioctls.all(Ioctls::COPY | Ioctls::ZEROPAGE | Ioctls::WAKE));
+
+        let handler = std::thread::spawn(move || {
+            let mut buffer = [0; 64];
+            for page in 0..2 {
+                let Some(Event::Pagefault { flags, address, .. }) = uffd.read(&mut buffer).unwrap().next() else {
+                    panic!("expected a page fault")
+                };
+                assert!(!flags.any(PagefaultFlags::WRITE));
+                let address = address as usize & !(4096 - 1);
+                assert_eq!(address, start + page * 4096);
+                if page == 0 {
+                    assert_eq!(uffd.copy(address, &[0x5A; 4096], CopyMode::NONE).unwrap(), 4096);
+                    assert_eq!(uffd.copy(address, &[0x5A; 4096], CopyMode::NONE).unwrap_err(), Error::EEXIST);
+                } else {
+                    assert_eq!(uffd.zeropage(Range::new(address, 4096), ZeropageMode::NONE).unwrap(), 4096);
+                }
+            }
+            uffd
+        });
+        assert_eq!(mapping[100], 0x5A);
+        assert_eq!(mapping[4096 + 100], 0);
+        let uffd = handler.join().unwrap();
+        uffd.unregister(Range::new(start, 2 * 4096)).unwrap();
+
+        let mut message = [0; 64];
+        message[0] = 0x16;
+        message[8..16].copy_from_slice(&0x1000u64.to_ne_bytes());
+        message[16..24].copy_from_slice(&0x3000u64.to_ne_bytes());
+        message[32] = 0x7F;
+        let mut events = Events::new(&message);
+        assert!(matches!(events.next(), Some(Event::Unmap { start: 0x1000, end: 0x3000 })));
+        assert!(matches!(events.next(), Some(Event::Unknown(0x7F))));
+        assert!(events.next().is_none());
+    }
+
+    #[test]
+    fn test_remote_memory() {
+        use crate::sys::*;
+        use crate::mmap::{Mapping, Protection};
+        use crate::remote::RemoteMemory;
+        let memory = RemoteMemory::new(getpid());
+        let values = [0x1234_5678u32, 0x9ABC_DEF0];
+        assert_eq!(memory.read::<[u32; 2]>(values.as_ptr() as usize).unwrap(), values);
+        assert_eq!(memory.read::<u16>(values.as_ptr() as usize + 1).unwrap(), u16::from_ne_bytes([0x56, 0x34]));
+        let string = c"hello";
+        let mut buffer = [0; 16];
+        assert_eq!(memory.read_c_str_into(string.as_ptr() as usize, &mut buffer).unwrap(), b"hello");
+        assert_eq!(memory.read_c_str_into(string.as_ptr() as usize, &mut buffer[..5]).unwrap_err(), Error::ERANGE);
+        assert_eq!(memory.read_c_string(string.as_ptr() as usize).unwrap().as_bytes(), b"hello");
+
+        // A string ending at the last byte before an unreadable page, and one running in to it
+        let mut mapping = Mapping::anonymous(2 * 4096).unwrap();
+        let end = mapping.as_ptr() as usize + 4096;
+        mapping.as_mut_slice().unwrap()[4096 - 4..4096].copy_from_slice(b"abc\0");
+        unsafe { mprotect(end as *mut _, 4096, Protection::NONE).unwrap() };
+        assert_eq!(memory.read_c_str_into(end - 4, &mut buffer).unwrap(), b"abc");
+        assert_eq!(memory.read_c_string(end - 4).unwrap().as_bytes(), b"abc");
+        assert_eq!(memory.read_c_str_into(end - 2, &mut buffer).unwrap(), b"c");
+        unsafe { *mapping.as_ptr().add(4095) = b'd' };
+        assert_eq!(memory.read_c_str_into(end - 4, &mut buffer).unwrap_err(), Error::EFAULT);
+        assert_eq!(memory.read_c_string(end - 4).unwrap_err(), Error::EFAULT);
+
+        // Partial transfers stop at the unreadable page
+        assert_eq!(memory.read_some(end - 4, &mut buffer).unwrap(), 4);
+        assert_eq!(memory.read_exact(end - 4, &mut buffer).unwrap_err(), Error::EFAULT);
+        assert_eq!(memory.read_some(end, &mut buffer).unwrap_err(), Error::EFAULT);
+
+        let mut first = [0; 3];
+        let mut second = [0; 5];
+        let source = b"abcdefgh";
+        let remote = [RemoteIoVec::new(source.as_ptr() as usize, 4), RemoteIoVec::new(source.as_ptr() as usize + 4, 4)];
+        let count = process_vm_readv(getpid(), &[IoVecMut::new(&mut first), IoVecMut::new(&mut second)], &remote).unwrap();
+        assert_eq!((count, &first, &second), (8, b"abc", b"defgh"));
+        let mut destination = [0u8; 8];
+        let remote = [RemoteIoVec::new(destination.as_mut_ptr() as usize, 8)];
+        assert_eq!(unsafe { process_vm_writev(getpid(), &[IoVec::new(b"12345678")], &remote) }.unwrap(), 8);
+        assert_eq!(&destination, b"12345678");
+    }
+
+    #[test]
+    fn test_channel() {
+        use crate::sys::*;
+        use crate::channel::Channel;
+        use crate::sock::{self, Ancillary, AncillaryType, Level};
+        use core::time::Duration;
+        assert_eq!(Channel::<u64>::new(3).unwrap_err(), Error::EINVAL);
+        let mut channel = Channel::<[u32; 2]>::new(4).unwrap();
+        let (sender, mut receiver) = channel.split();
+        assert_eq!(receiver.try_recv().unwrap_err(), Error::EAGAIN);
+        assert_eq!(receiver.recv_timeout(Duration::from_millis(10)).unwrap_err(), Error::ETIMEDOUT);
+        for i in 0..4 {
+            sender.try_send([i, i * 2]).unwrap();
+        }
+        assert_eq!(sender.try_send([4, 8]).unwrap_err(), Error::EAGAIN);
+        assert_eq!(sender.send_timeout([4, 8], Duration::from_millis(10)).unwrap_err(), Error::ETIMEDOUT);
+        for i in 0..4 {
+            assert_eq!(receiver.recv().unwrap(), [i, i * 2]);
+        }
+
+        // Several senders blocking on a small ring, with the receiver blocking on an empty one
+        const SENDERS: u32 = 4;
+        const MESSAGES: u32 = 10_000;
+        let mut received = [0; SENDERS as usize];
+        std::thread::scope(|scope| {
+            for id in 0..SENDERS {
+                scope.spawn(move || {
+                    for i in 0..MESSAGES {
+                        sender.send([id, i]).unwrap();
+                    }
+                });
+            }
+            for _ in 0..SENDERS * MESSAGES {
+                let [id, i] = receiver.recv().unwrap();
+                // Messages from each sender arrive in order
+                assert_eq!(received[id as usize], i);
+                received[id as usize] += 1;
+            }
+        });
+        assert_eq!(received, [MESSAGES; SENDERS as usize]);
+
+        // Hand the channel over a socket, as to another process
+        let (parent, child) = socketpair(sock::Domain::UNIX, sock::Type::STREAM | sock::TypeFlags::CLOSE_ON_EXEC, sock::Protocol::UNSPECIFIED).unwrap();
+        let rights = Ancillary::<u32, 1>::with_data(&[channel.file().raw()], AncillaryType::RIGHTS, Level::SOCKET);
+        sendmsg(&parent, &[IoVec::new(b"c")], Some(&rights), sock::Flags::NONE).unwrap();
+        let mut rights = Ancillary::<u32, 1>::new();
+        let mut byte = [0];
+        recvmsg(&child, &[IoVecMut::new(&mut byte)], Some(&mut rights), sock::Flags::CLOSE_ON_EXEC).unwrap();
+        assert_eq!(rights.items().len(), 1);
+        let file = Fd::from_raw(unsafe { rights.items()[0].assume_init() }).owned();
+        let peer = unsafe { Channel::<[u32; 2]>::from_file(file).unwrap() };
+        std::thread::scope(|scope| {
+            scope.spawn(|| peer.sender().send([7, 8]).unwrap());
+            assert_eq!(channel.receiver().recv().unwrap(), [7, 8]);
+        });
+        let other = unsafe { Channel::<u64>::from_file(memfd_create("test_channel", MemfdFlags::ALLOW_SEALING).unwrap()) };
+        assert_eq!(other.unwrap_err(), Error::EINVAL);
+        let duplicate = Fd::from_raw(fcntl(channel.file(), Fcntl::DupFd(Fd::from_raw(0))).unwrap()).owned();
+        assert_eq!(unsafe { Channel::<u64>::from_file(duplicate) }.unwrap_err(), Error::EINVAL);
+    }
+
+    #[test]
+    fn test_membarrier_mempolicy() {
+        use crate::sys::*;
+        use crate::mmap::Mapping;
+        use crate::mempolicy::{self, GetFlags, MemPolicy, ModeFlags, MoveFlags, NodeMask};
+        let supported = membarrier(MembarrierCmd::QUERY, None).unwrap();
+        if MembarrierCmd::PRIVATE_EXPEDITED.is_in(supported) {
+            membarrier(MembarrierCmd::REGISTER_PRIVATE_EXPEDITED, None).unwrap();
+            membarrier(MembarrierCmd::PRIVATE_EXPEDITED, None).unwrap();
+            let registered = membarrier(MembarrierCmd::GET_REGISTRATIONS, None);
+            assert!(registered.is_err() || MembarrierCmd::REGISTER_PRIVATE_EXPEDITED.is_in(registered.unwrap()));
+        }
+        assert_eq!(membarrier(MembarrierCmd::PRIVATE_EXPEDITED, Some(0)).unwrap_err(), Error::EINVAL);
+
+        let mut mask: NodeMask = [0, 3, 1023].into_iter().collect();
+        assert_eq!(mask.nodes().collect::<Vec<_>>(), [0, 3, 1023]);
+        assert!(!mask.insert(3) && mask.remove(3) && !mask.contains(3) && !mask.contains(5000));
+        assert_eq!(format!("{mask:?}"), "{0, 1023}");
+
+        let mut allowed = NodeMask::new();
+        match get_mempolicy(Some(&mut allowed), core::ptr::null(), GetFlags::MEMS_ALLOWED) {
+            // Built without NUMA support
+            Err(Error::ENOSYS) => return,
+            result => result.unwrap()
+        };
+        let node = allowed.nodes().next().unwrap();
+        let nodes: NodeMask = [node].into_iter().collect();
+        let mode = get_mempolicy(None, core::ptr::null(), GetFlags::NONE).unwrap();
+        assert_eq!(mempolicy::split_mode(mode), (MemPolicy::DEFAULT, ModeFlags::NONE));
+
+        // A thread has its own policy
+        std::thread::spawn(move || {
+            set_mempolicy(MemPolicy::BIND, ModeFlags::STATIC_NODES, Some(&nodes)).unwrap();
+            let mut bound = NodeMask::new();
+            let mode = get_mempolicy(Some(&mut bound), core::ptr::null(), GetFlags::NONE).unwrap();
+            assert_eq!(mempolicy::split_mode(mode), (MemPolicy::BIND, ModeFlags::STATIC_NODES));
+            assert_eq!(bound, nodes);
+            set_mempolicy(MemPolicy::DEFAULT, ModeFlags::NONE, None).unwrap();
+        }).join().unwrap();
+
+        let mut mapping = Mapping::anonymous(2 * 4096).unwrap();
+        mbind(mapping.as_ptr() as *mut _, mapping.len(), MemPolicy::PREFERRED, ModeFlags::NONE, Some(&nodes), MoveFlags::NONE).unwrap();
+        mapping.as_mut_slice().unwrap()[0] = 1;
+        let mut preferred = NodeMask::new();
+        let mode = get_mempolicy(Some(&mut preferred), mapping.as_ptr() as *const _, GetFlags::ADDRESS).unwrap();
+        assert_eq!(MemPolicy::from(mode), MemPolicy::PREFERRED);
+        assert_eq!(preferred, nodes);
+        let on = get_mempolicy(None, mapping.as_ptr() as *const _, GetFlags::ADDRESS | GetFlags::NODE).unwrap();
+        assert_eq!(on, node);
+
+        let pages = [mapping.as_ptr() as *const _, unsafe { mapping.as_ptr().add(4096) } as *const _];
+        let mut status = [0; 2];
+        assert_eq!(move_pages(getpid(), &pages, None, &mut status, MoveFlags::NONE).unwrap(), 0);
+        assert_eq!(status, [node as i32, -2]);
+        assert_eq!(move_pages(getpid(), &pages, Some(&[node as i32; 2]), &mut status, MoveFlags::MOVE).unwrap(), 0);
+        assert_eq!(status[0], node as i32);
+        assert_eq!(move_pages(getpid(), &pages, None, &mut status[..1], MoveFlags::NONE).unwrap_err(), Error::EINVAL);
+    }
+
+    #[test]
+    fn test_secret_buf() {
+        use crate::Error;
+        use crate::secret::{self, SecretBuf};
+        let mut key = match SecretBuf::new(100) {
+            // Locked memory is limited by RLIMIT_MEMLOCK
+            Err(Error::ENOMEM | Error::EPERM) => return,
+            key => key.unwrap()
+        };
+        assert_eq!(key.len(), 100);
+        assert!(key.iter().all(|&b| b == 0));
+        key[..5].copy_from_slice(b"hello");
+        let mut expected = [0; 100];
+        expected[..5].copy_from_slice(b"hello");
+        assert!(key.ct_eq(&expected));
+        assert!(!key.ct_eq(b"hello"));
+        let mut other = SecretBuf::from_slice(&key).unwrap();
+        assert_eq!(key, other);
+        other[99] = 1;
+        assert_ne!(key, other);
+        assert!(secret::ct_eq(b"", b"") && !secret::ct_eq(b"a", b"b") && !secret::ct_eq(b"a", b"ab"));
+        assert!(!format!("{key:?}").contains("104"));
+
+        // The data pages are hardened, between inaccessible guard pages
+        let smaps = std::fs::read_to_string("/proc/self/smaps").unwrap();
+        // Each mapping is a line with its range and protection, followed by fields ending with its flags
+        let ranges = smaps.lines().filter(|line| line.split(' ').next().unwrap().contains('-')).collect::<Vec<_>>();
+        let flags = smaps.lines().filter_map(|line| line.strip_prefix("VmFlags:")).collect::<Vec<_>>();
+        let index = ranges.iter().position(|range| range.starts_with(&format!("{:x}-", key.as_ptr() as usize))).unwrap();
+        assert!(flags[index].contains(" lo") && flags[index].contains(" dd"), "{}", flags[index]);
+        if !key.is_secret() {
+            assert!(flags[index].contains(" wf"), "{}", flags[index]);
+        }
+        assert!(ranges[index - 1].contains(" ---p ") && ranges[index + 1].contains(" ---p "));
+    }
+
+    #[test]
+    fn test_clone() {
+        use crate::sys::*;
+        use crate::clone::{CloneArgs, Flags, Fork, WaitFlags};
+        use core::sync::atomic::{AtomicU32, Ordering};
+        let pid = match unsafe { fork() }.unwrap() {
+            Fork::Child => exit(7),
+            Fork::Parent { pid, pidfd } => {
+                assert!(pidfd.is_none());
+                pid
+            }
+        };
+        let (waited, status) = wait4(pid as i32, WaitFlags::NONE).unwrap();
+        assert_eq!((waited, status.exit_code(), status.signal()), (pid, Some(7), None));
+
+        let args = CloneArgs::new().flags(Flags::PIDFD);
+        let (pid, pidfd) = match unsafe { clone3(&args) }.unwrap() {
+            Fork::Child => exit(if getpid() == 1 { 1 } else { 3 }),
+            Fork::Parent { pid, pidfd } => (pid, pidfd.unwrap())
+        };
+        assert!(fstat(&pidfd).is_ok());
+        assert_eq!(wait4(pid as i32, WaitFlags::NONE).unwrap().1.exit_code(), Some(3));
+
+        // A child in new namespaces is the first process of its PID namespace
+        let args = CloneArgs::new().flags(Flags::NEW_USER | Flags::NEW_PID | Flags::NEW_NET | Flags::NEW_MOUNT);
+        match unsafe { clone3(&args) } {
+            Ok(Fork::Child) => exit(if getpid() == 1 { 0 } else { 1 }),
+            Ok(Fork::Parent { pid, .. }) => assert_eq!(wait4(pid as i32, WaitFlags::NONE).unwrap().1.exit_code(), Some(0)),
+            // Namespaces may be restricted
+            Err(e) => assert!(matches!(e, Error::EPERM | Error::ENOSPC | Error::EINVAL), "{e:?}")
+        }
+
+        // Without an exit signal the child can only be waited for with ALL
+        let args = CloneArgs::new().exit_signal(0);
+        let pid = match unsafe { clone3(&args) }.unwrap() {
+            Fork::Child => exit(0),
+            Fork::Parent { pid, .. } => pid
+        };
+        assert_eq!(wait4(pid as i32, WaitFlags::NONE).unwrap_err(), Error::ECHILD);
+        assert_eq!(wait4(pid as i32, WaitFlags::ALL).unwrap().0, pid);
+
+        assert_eq!(unsafe { clone3(&CloneArgs::new().flags(Flags::VM)) }.unwrap_err(), Error::EINVAL);
+        assert_eq!(unsafe { clone3(&CloneArgs::new().set_tid(&[1; 64])) }.unwrap_err(), Error::EINVAL);
+        assert!(CloneArgs::new().cgroup(Fd::from_raw(0)).flags(Flags::PIDFD).get_flags().all(Flags::PIDFD | Flags::INTO_CGROUP));
+
+        // The parent is suspended until the child exits, and sees its writes
+        let shared = AtomicU32::new(0);
+        let mut stack = vec![0; 64 * 1024];
+        let pid = unsafe {
+            vfork(&mut stack, || {
+                shared.store(getpid(), Ordering::Relaxed);
+                5
+            })
+        }.unwrap();
+        assert_eq!(shared.load(Ordering::Relaxed), pid);
+        assert_eq!(wait4(pid as i32, WaitFlags::NONE).unwrap().1.exit_code(), Some(5));
+    }
+
+    #[test]
+    fn test_exec() {
+        use crate::sys::*;
+        use crate::clone::WaitFlags;
+        use crate::exec::{execvpe, Arena, Argv};
+        let mut buffer = [0; 1024];
+        let mut arena = Arena::new(&mut buffer);
+        let argv = arena.argv(&["sh", "-c", "exit $CODE"]).unwrap();
+        let envp = arena.argv(&[b"CODE=3".as_slice()]).unwrap();
+        assert_eq!(argv.len(), 3);
+        assert_eq!(argv.get(2).unwrap().to_bytes(), b"exit $CODE");
+        assert!(argv.get(3).is_none());
+        assert!(Argv::EMPTY.is_empty());
+        assert_eq!(arena.c_str(b"a\0b").unwrap_err(), Error::EINVAL);
+        let remaining = arena.remaining();
+        assert_eq!(arena.c_str(vec![b'a'; remaining]).unwrap_err(), Error::ERANGE);
+        assert_eq!(arena.c_str(vec![b'a'; remaining - 1]).unwrap().to_bytes().len(), remaining - 1);
+        assert_eq!(arena.argv(&[""; 0]).unwrap_err(), Error::ERANGE);
+
+        let mut stack = vec![0; 64 * 1024];
+        let mut run = |child: &mut dyn FnMut() -> Error| {
+            let pid = unsafe { vfork(&mut stack, || u32::from(child()) as i32) }.unwrap();
+            wait4(pid as i32, WaitFlags::NONE).unwrap().1.exit_code()
+        };
+        assert_eq!(run(&mut || execve(c"/bin/sh", &argv, &envp)), Some(3));
+        let sh = open("/bin/sh", open::Flags::READ_ONLY | open::Flags::CLOSE_ON_EXEC, open::Mode::NONE).unwrap();
+        assert_eq!(run(&mut || fexecve(&sh, &argv, &envp)), Some(3));
+        // Missing directories and files that are not directories are skipped
+        assert_eq!(run(&mut || execvpe(c"sh", &argv, &envp, b"/nonexistent:/etc/passwd::/bin")), Some(3));
+        assert_eq!(run(&mut || execvpe(c"nonexistent", &argv, &envp, b"/bin")), Some(2));
+
+        // Failures return without replacing the test process
+        assert_eq!(execveat(Fd::cwd, c"/proc/self/exe", &argv, &envp, AtFlags::SYMLINK_NO_FOLLOW), Error::ELOOP);
+        assert_eq!(execvpe(c"", &argv, &envp, b"/bin"), Error::ENOENT);
+        assert_eq!(execvpe(c"/nonexistent/sh", &argv, &envp, b"/bin"), Error::ENOENT);
+        assert_eq!(execvpe(c"sh", &argv, &envp, b"/nonexistent:/proc/self"), Error::ENOENT);
+        // A file that may not be executed is remembered while the search continues
+        assert_eq!(execvpe(c"passwd", &argv, &envp, b"/etc:/nonexistent"), Error::EACCES);
+        assert_eq!(execvpe(c"passwd", &argv, &envp, b"/etc/passwd:/etc"), Error::EACCES);
+    }
+}
@@ -0,0 +1,190 @@
+//! A zero-allocation parser for `/proc/self/mountinfo`.
+//!
+//! Each line describes a mount:
+//! ```text
+//! 36 35 98:0 /mnt1 /mnt/parent rw,noatime master:1 - ext3 /dev/root rw,errors=continue
+//! ```
+use crate::{Device, Error};
+
+/// A field in which space, tab, newline and backslash are escaped as octal, such as `\040` for a space.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct Escaped<'a>(&'a [u8]);
+impl<'a> Escaped<'a> {
+    /// The raw, escaped bytes.
+    pub fn raw(&self) -> &'a [u8] {
+        self.0
+    }
+    /// Iterate the decoded bytes.
+    pub fn bytes(&self) -> Unescape<'a> {
+        Unescape(self.0)
+    }
+    /// Decode the field in to a buffer, returning the filled portion.
+    ///
+    /// Returns `ERANGE` if the buffer is too small.
+    pub fn decode<'b>(&self, buffer: &'b mut [u8]) -> Result<&'b [u8], Error> {
+        let mut len = 0;
+        for b in self.bytes() {
+            *buffer.get_mut(len).ok_or(Error::ERANGE)? = b;
+            len += 1;
+        }
+        Ok(&buffer[..len])
+    }
+}
+impl<'a> PartialEq<[u8]> for Escaped<'a> {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.bytes().eq(other.iter().copied())
+    }
+}
+impl<'a, const N: usize> PartialEq<[u8; N]> for Escaped<'a> {
+    fn eq(&self, other: &[u8; N]) -> bool {
+        *self == other[..]
+    }
+}
+impl<'a> core::fmt::Debug for Escaped<'a> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "\"{}\"", self.0.escape_ascii())
+    }
+}
+
+/// An iterator over the decoded bytes of an `Escaped` field.
+#[derive(Debug, Clone)]
+pub struct Unescape<'a>(&'a [u8]);
+impl<'a> Iterator for Unescape<'a> {
+    type Item = u8;
+    fn next(&mut self) -> Option<Self::Item> {
+        let (&b, rest) = self.0.split_first()?;
+        if let [b'\\', a @ b'0'..=b'3', b @ b'0'..=b'7', c @ b'0'..=b'7', ..] = self.0 {
+            self.0 = &self.0[4..];
+            return Some(((a - b'0') << 6) | ((b - b'0') << 3) | (c - b'0'))
+        }
+        self.0 = rest;
+        Some(b)
+    }
+}
+
+/// A propagation field describing the peer group of a mount.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OptionalField<'a> {
+    /// The mount is shared in the peer group.
+    Shared(u32),
+    /// The mount receives events from the peer group.
+    Master(u32),
+    /// The mount receives events from the peer group, which is the nearest dominant peer group visible to the process.
+    PropagateFrom(u32),
+    /// The mount cannot be bind mounted.
+    Unbindable,
+    /// A field not recognised by this parser.
+    Unknown(&'a [u8])
+}
+impl<'a> OptionalField<'a> {
+    fn parse(field: &'a [u8]) -> Self {
+        let id = |tag: &[u8]| field.strip_prefix(tag).and_then(parse_u32);
+        if let Some(id) = id(b"shared:") {
+            Self::Shared(id)
+        } else if let Some(id) = id(b"master:") {
+            Self::Master(id)
+        } else if let Some(id) = id(b"propagate_from:") {
+            Self::PropagateFrom(id)
+        } else if field == b"unbindable" {
+            Self::Unbindable
+        } else {
+            Self::Unknown(field)
+        }
+    }
+}
+
+/// A single mount, borrowing from the `mountinfo` contents.
+#[derive(Debug, Clone, Copy)]
+pub struct MountInfo<'a> {
+    pub id: u32,
+    pub parent_id: u32,
+    pub device: Device,
+    /// The directory of the file system that forms the root of the mount.
+    pub root: Escaped<'a>,
+    /// The location of the mount, relative to the root of the process.
+    pub mount_point: Escaped<'a>,
+    /// The comma-separated per-mount options.
+    pub options: &'a [u8],
+    optional_fields: &'a [u8],
+    pub fs_type: Escaped<'a>,
+    pub source: Escaped<'a>,
+    /// The comma-separated per-superblock options.
+    pub super_options: &'a [u8]
+}
+impl<'a> MountInfo<'a> {
+    /// Parse a single line of `mountinfo`, without the trailing newline.
+    pub fn parse(line: &'a [u8]) -> Result<Self, Error> {
+        let separator = line.windows(3).position(|w| w == b" - ").ok_or(Error::EINVAL)?;
+        let (head, tail) = (&line[..separator], &line[separator + 3..]);
+        let mut fields = head.splitn(7, |&b| b == b' ');
+        let mut next = || fields.next().ok_or(Error::EINVAL);
+        let id = parse_u32(next()?).ok_or(Error::EINVAL)?;
+        let parent_id = parse_u32(next()?).ok_or(Error::EINVAL)?;
+        let device = next()?;
+        let colon = device.iter().position(|&b| b == b':').ok_or(Error::EINVAL)?;
+        let major = parse_u32(&device[..colon]).ok_or(Error::EINVAL)?;
+        let minor = parse_u32(&device[colon + 1..]).ok_or(Error::EINVAL)?;
+        let root = Escaped(next()?);
+        let mount_point = Escaped(next()?);
+        let options = next()?;
+        let optional_fields = fields.next().unwrap_or_default();
+        let mut fields = tail.splitn(3, |&b| b == b' ');
+        let mut next = || fields.next().ok_or(Error::EINVAL);
+        Ok(Self {
+            id,
+            parent_id,
+            device: Device::new(major, minor),
+            root,
+            mount_point,
+            options,
+            optional_fields,
+            fs_type: Escaped(next()?),
+            source: Escaped(next()?),
+            super_options: next()?
+        })
+    }
+    /// Iterate the propagation fields.
+    pub fn optional_fields(&self) -> impl Iterator<Item = OptionalField<'a>> + 'a {
+        self.optional_fields.split(|&b| b == b' ').filter(|field| !field.is_empty()).map(OptionalField::parse)
+    }
+}
+
+/// An iterator over the mounts described by the contents of a `mountinfo` file.
+#[derive(Debug, Clone)]
+pub struct Mounts<'a>(&'a [u8]);
+impl<'a> Mounts<'a> {
+    pub fn new(mountinfo: &'a [u8]) -> Self {
+        Self(mountinfo)
+    }
+}
+impl<'a> Iterator for Mounts<'a> {
+    type Item = Result<MountInfo<'a>, Error>;
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.0.is_empty() {
+            let (line, rest) = match self.0.iter().position(|&b| b == b'\n') {
+                Some(end) => (&self.0[..end], &self.0[end + 1..]),
+                None => (self.0, &[][..])
+            };
+            self.0 = rest;
+            if !line.is_empty() {
+                return Some(MountInfo::parse(line))
+            }
+        }
+        None
+    }
+}
+
+/// Split comma-separated options.
+pub fn options(options: &[u8]) -> impl Iterator<Item = &[u8]> {
+    options.split(|&b| b == b',').filter(|option| !option.is_empty())
+}
+
+fn parse_u32(digits: &[u8]) -> Option<u32> {
+    if digits.is_empty() {
+        return None
+    }
+    digits.iter().try_fold(0u32, |n, &b| match b {
+        b'0'..=b'9' => n.checked_mul(10)?.checked_add((b - b'0') as u32),
+        _ => None
+    })
+}
@@ -0,0 +1,117 @@
+//! Reading the memory of another process, such as from a debugger or crash reporter.
+//!
+//! Addresses are in the address space of the target process. Reads are bounds-checked by the kernel, so a bad address
+//! is reported as `EFAULT` rather than faulting.
+use core::mem::MaybeUninit;
+use crate::{process_vm_readv, Error, IoVecMut, RemoteIoVec, mmap::PAGE_SIZE};
+
+/// A type that is valid for any bit pattern, so that it may be read from the memory of another process.
+///
+/// # Safety
+/// Every bit pattern of the size of the type must be a valid value, so it must not contain references, `bool`, `char`,
+/// enums or padding.
+pub unsafe trait Plain: Copy {}
+macro_rules! plain {
+    ($($ty:ty),*) => {
+        $(unsafe impl Plain for $ty {})*
+    };
+}
+plain!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+unsafe impl<T: Plain, const N: usize> Plain for [T; N] {}
+
+/// The memory of another process.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteMemory {
+    pid: u32
+}
+impl RemoteMemory {
+    /// Access the memory of `pid`. This requires the same permission as `ptrace` attaching to it, which is checked on
+    /// each read.
+    pub fn new(pid: u32) -> Self {
+        Self { pid }
+    }
+    pub fn pid(&self) -> u32 {
+        self.pid
+    }
+    /// Read from `address` in to a buffer, returning the number of bytes read.
+    ///
+    /// The count is short if the region is only partly readable.
+    pub fn read_some(&self, address: usize, buffer: &mut [u8]) -> Result<usize, Error> {
+        if buffer.is_empty() {
+            return Ok(0)
+        }
+        let remote = RemoteIoVec::new(address, buffer.len());
+        process_vm_readv(self.pid, &[IoVecMut::new(buffer)], &[remote])
+    }
+    /// Fill a buffer from `address`, retrying on partial transfers.
+    ///
+    /// Returns `EFAULT` if any of the region is not readable.
+    pub fn read_exact(&self, mut address: usize, mut buffer: &mut [u8]) -> Result<(), Error> {
+        while !buffer.is_empty() {
+            match self.read_some(address, buffer) {
+                Ok(0) => return Err(Error::EFAULT),
+                Ok(count) => {
+                    address += count;
+                    buffer = &mut buffer[count..];
+                },
+                Err(Error::EINTR) => (),
+                Err(e) => return Err(e)
+            }
+        }
+        Ok(())
+    }
+    /// Read a value from `address`, which need not be aligned.
+    pub fn read<T: Plain>(&self, address: usize) -> Result<T, Error> {
+        let mut value = MaybeUninit::<T>::uninit();
+        // Safety: the buffer covers the value, and every bit pattern is a valid T once it is filled
+        let buffer = unsafe { core::slice::from_raw_parts_mut(value.as_mut_ptr() as *mut u8, core::mem::size_of::<T>()) };
+        self.read_exact(address, buffer)?;
+        Ok(unsafe { value.assume_init() })
+    }
+    /// Read a null-terminated string from `address` in to a buffer, returning it without the terminator.
+    ///
+    /// Memory is read a page at a time, so a string ending just before an unreadable page is read successfully.
+    /// Returns `ERANGE` if the buffer is too small, or `EFAULT` if the string runs in to unreadable memory.
+    pub fn read_c_str_into<'a>(&self, address: usize, buffer: &'a mut [u8]) -> Result<&'a [u8], Error> {
+        let mut len = 0;
+        loop {
+            let available = buffer.len() - len;
+            if available == 0 {
+                return Err(Error::ERANGE)
+            }
+            // Never read past the end of the page, which may be the last readable one
+            let page_end = (address + len + 1).next_multiple_of(PAGE_SIZE);
+            let chunk = available.min(page_end - (address + len));
+            let count = self.read_some(address + len, &mut buffer[len..len + chunk])?;
+            if count == 0 {
+                return Err(Error::EFAULT)
+            }
+            if let Some(end) = buffer[len..len + count].iter().position(|&b| b == 0) {
+                return Ok(&buffer[..len + end])
+            }
+            len += count;
+        }
+    }
+    /// Read a null-terminated string from `address`.
+    ///
+    /// Returns `EFAULT` if the string runs in to unreadable memory.
+    #[cfg(feature = "std")]
+    pub fn read_c_string(&self, address: usize) -> Result<std::ffi::CString, Error> {
+        let mut string = Vec::new();
+        let mut page = [0; PAGE_SIZE];
+        loop {
+            let start = address + string.len();
+            let chunk = (start + 1).next_multiple_of(PAGE_SIZE) - start;
+            let count = self.read_some(start, &mut page[..chunk])?;
+            if count == 0 {
+                return Err(Error::EFAULT)
+            }
+            if let Some(end) = page[..count].iter().position(|&b| b == 0) {
+                string.extend_from_slice(&page[..end]);
+                // Safety: the string stops at the first null byte
+                return Ok(unsafe { std::ffi::CString::from_vec_unchecked(string) })
+            }
+            string.extend_from_slice(&page[..count]);
+        }
+    }
+}
@@ -0,0 +1,136 @@
+//! A byte ring buffer whose free and filled regions are always contiguous.
+//!
+//! The data pages of a memfd are mapped twice, back to back, so that a slice running off the end of the first mapping
+//! continues seamlessly in to the start of the second. The read and write counters live in a header page of the same
+//! memfd, so a ring can be shared with another process by passing the memfd, such as with `sendmsg`.
+use core::sync::atomic::{AtomicUsize, Ordering};
+use crate::{
+    fstat, memfd_create_unsafe, mmap_anonymous, mmap_unsafe,
+    mmap::{Flags, Mapping, Protection, PAGE_SIZE},
+    Error, File, MemfdFlags, Seals
+};
+
+/// A counter on its own cache line, so that the reader and writer do not contend.
+#[repr(C, align(64))]
+struct Counter(AtomicUsize);
+
+/// The shared header page at the start of the memfd.
+#[repr(C)]
+struct Header {
+    /// The total number of bytes committed by the writer.
+    write: Counter,
+    /// The total number of bytes consumed by the reader.
+    read: Counter
+}
+
+/// A single-producer, single-consumer byte ring buffer.
+///
+/// Within one process a single `RingBuffer` may both write and read. When shared, each process should only write or
+/// only read.
+#[derive(Debug)]
+pub struct RingBuffer {
+    file: File,
+    /// The reservation holding the header page followed by the two views of the data pages.
+    mapping: Mapping,
+    capacity: usize
+}
+impl RingBuffer {
+    /// Create an empty ring backed by a new memfd. `capacity` must be a non-zero multiple of `mmap::PAGE_SIZE`.
+    ///
+    /// The memfd is sealed so that it cannot be resized, as a peer shrinking it would fault the other mappings.
+    pub fn new(capacity: usize) -> Result<Self, Error> {
+        if capacity == 0 || !capacity.is_multiple_of(PAGE_SIZE) {
+            return Err(Error::EINVAL)
+        }
+        let file = unsafe { memfd_create_unsafe(c"syslib_ring".as_ptr() as *const u8, MemfdFlags::CLOSE_ON_EXEC | MemfdFlags::ALLOW_SEALING)? };
+        file.truncate((PAGE_SIZE + capacity) as u64)?;
+        file.add_seals(Seals::SHRINK | Seals::GROW | Seals::SEAL)?;
+        Self::map(file, capacity)
+    }
+    /// Open a ring shared by another process.
+    ///
+    /// The memfd must have been created by `RingBuffer::new`; `EINVAL` is returned if its size is unsuitable or it
+    /// is not sealed against shrinking.
+    ///
+    /// # Safety
+    /// The peer must not write in to the filled region or read from the free region of the ring, and must only
+    /// advance its own counter. The counters are bounds-checked, but a peer that breaks the protocol causes data races.
+    pub unsafe fn from_file(file: File) -> Result<Self, Error> {
+        if !file.seals()?.any(Seals::SHRINK) {
+            return Err(Error::EINVAL)
+        }
+        let size = fstat(&file)?.size as usize;
+        let capacity = size.checked_sub(PAGE_SIZE).ok_or(Error::EINVAL)?;
+        if capacity == 0 || !capacity.is_multiple_of(PAGE_SIZE) {
+            return Err(Error::EINVAL)
+        }
+        Self::map(file, capacity)
+    }
+    fn map(file: File, capacity: usize) -> Result<Self, Error> {
+        let length = PAGE_SIZE + 2 * capacity;
+        let address = mmap_anonymous(0, length, Protection::NONE, Flags::PRIVATE | Flags::NO_RESERVE)?;
+        // Owning the reservation unmaps everything if a later mapping fails
+        let mapping = unsafe { Mapping::from_raw(address, length, Protection::NONE) };
+        let protection = Protection::READ | Protection::WRITE;
+        let flags = Flags::SHARED | Flags::FIXED;
+        let base = address as usize;
+        // Safety: every mapping is placed inside the reservation, which nothing else refers to yet
+        unsafe {
+            mmap_unsafe(base, PAGE_SIZE, protection, flags, &file, 0)?;
+            mmap_unsafe(base + PAGE_SIZE, capacity, protection, flags, &file, PAGE_SIZE)?;
+            mmap_unsafe(base + PAGE_SIZE + capacity, capacity, protection, flags, &file, PAGE_SIZE)?;
+        }
+        Ok(Self { file, mapping, capacity })
+    }
+    /// The memfd backing the ring, for sharing with another process.
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    fn header(&self) -> &Header {
+        unsafe { &*(self.mapping.as_ptr() as *const Header) }
+    }
+    fn data(&self) -> *mut u8 {
+        unsafe { self.mapping.as_ptr().add(PAGE_SIZE) }
+    }
+    /// The number of bytes committed but not yet consumed.
+    pub fn len(&self) -> usize {
+        let header = self.header();
+        let write = header.write.0.load(Ordering::Acquire);
+        let read = header.read.0.load(Ordering::Acquire);
+        write.wrapping_sub(read).min(self.capacity)
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// The committed bytes, in order, as one contiguous slice.
+    pub fn readable(&self) -> &[u8] {
+        let read = self.header().read.0.load(Ordering::Relaxed);
+        let len = self.len();
+        unsafe { core::slice::from_raw_parts(self.data().add(read % self.capacity), len) }
+    }
+    /// The free space following the committed bytes, as one contiguous slice.
+    pub fn writable(&mut self) -> &mut [u8] {
+        let write = self.header().write.0.load(Ordering::Relaxed);
+        let free = self.capacity - self.len();
+        unsafe { core::slice::from_raw_parts_mut(self.data().add(write % self.capacity), free) }
+    }
+    /// Make `count` bytes written to the start of `writable` available to the reader.
+    ///
+    /// # Panics
+    /// Panics if `count` exceeds the free space.
+    pub fn commit(&mut self, count: usize) {
+        assert!(count <= self.capacity - self.len(), "committed more bytes than are free");
+        self.header().write.0.fetch_add(count, Ordering::Release);
+    }
+    /// Release `count` bytes from the start of `readable` back to the writer.
+    ///
+    /// # Panics
+    /// Panics if `count` exceeds the committed bytes.
+    pub fn consume(&mut self, count: usize) {
+        assert!(count <= self.len(), "consumed more bytes than are committed");
+        self.header().read.0.fetch_add(count, Ordering::Release);
+    }
+}
@@ -0,0 +1,127 @@
+//! Memory for key material, hardened against leaking through swap, core dumps, forks and neighbouring overflows.
+use core::{ptr, sync::atomic::{compiler_fence, Ordering}};
+use crate::{
+    madvise, memfd_secret, mlock, mmap_anonymous, mmap_anonymous_unsafe, mmap_unsafe, open,
+    mmap::{Advice, Flags, Mapping, Protection, PAGE_SIZE},
+    Error
+};
+
+/// A fixed-size buffer for secrets, in its own mapping.
+///
+/// The buffer is surrounded by inaccessible guard pages, so that overruns fault rather than reading or writing
+/// neighbouring memory. Where the kernel supports it the memory comes from `memfd_secret`, which also removes it from
+/// the kernel's own mappings. Otherwise it is anonymous memory locked with `mlock`. Either way it is excluded from core
+/// dumps and is not visible to child processes, and is zeroed when dropped.
+pub struct SecretBuf {
+    /// The reservation holding the guard pages either side of the data pages.
+    mapping: Mapping,
+    len: usize,
+    secret: bool
+}
+impl SecretBuf {
+    /// Allocate a zeroed buffer of `len` bytes.
+    ///
+    /// Locked memory counts towards `RLIMIT_MEMLOCK`, and `ENOMEM` or `EPERM` is returned if it would be exceeded.
+    pub fn new(len: usize) -> Result<Self, Error> {
+        let data_len = len.max(1).checked_next_multiple_of(PAGE_SIZE).ok_or(Error::ENOMEM)?;
+        let length = data_len.checked_add(2 * PAGE_SIZE).ok_or(Error::ENOMEM)?;
+        let address = mmap_anonymous(0, length, Protection::NONE, Flags::PRIVATE | Flags::NO_RESERVE)?;
+        // Owning the reservation unmaps everything if a later step fails
+        let mapping = unsafe { Mapping::from_raw(address, length, Protection::NONE) };
+        let data = address as usize + PAGE_SIZE;
+        let protection = Protection::READ | Protection::WRITE;
+        let secret = match memfd_secret(open::Flags::CLOSE_ON_EXEC) {
+            Ok(file) => {
+                // Secret memory is already locked and excluded from core dumps, but is shared, so cannot be wiped
+                file.truncate(data_len as u64)?;
+                // Safety: the range is inside the reservation, which nothing else refers to yet
+                unsafe {
+                    mmap_unsafe(data, data_len, protection, Flags::SHARED | Flags::FIXED, &file, 0)?;
+                    madvise(data as *mut _, data_len, Advice::DONT_FORK)?;
+                }
+                true
+            },
+            Err(Error::ENOSYS) => {
+                unsafe { mmap_anonymous_unsafe(data, data_len, protection, Flags::PRIVATE | Flags::FIXED)? };
+                mlock(data as *const _, data_len)?;
+                unsafe {
+                    madvise(data as *mut _, data_len, Advice::DONT_DUMP)?;
+                    madvise(data as *mut _, data_len, Advice::WIPE_ON_FORK)?;
+                }
+                false
+            },
+            Err(e) => return Err(e)
+        };
+        Ok(Self { mapping, len, secret })
+    }
+    /// Allocate a buffer holding a copy of `bytes`.
+    ///
+    /// The caller remains responsible for clearing `bytes`.
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
+        let mut buffer = Self::new(bytes.len())?;
+        buffer.copy_from_slice(bytes);
+        Ok(buffer)
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// Whether the buffer is backed by `memfd_secret`, rather than locked anonymous memory.
+    pub fn is_secret(&self) -> bool {
+        self.secret
+    }
+    fn data(&self) -> *mut u8 {
+        unsafe { self.mapping.as_ptr().add(PAGE_SIZE) }
+    }
+    /// Compare the contents with `other` in time independent of where they differ.
+    pub fn ct_eq(&self, other: &[u8]) -> bool {
+        ct_eq(self, other)
+    }
+}
+impl core::ops::Deref for SecretBuf {
+    type Target = [u8];
+    fn deref(&self) -> &Self::Target {
+        unsafe { core::slice::from_raw_parts(self.data(), self.len) }
+    }
+}
+impl core::ops::DerefMut for SecretBuf {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { core::slice::from_raw_parts_mut(self.data(), self.len) }
+    }
+}
+impl PartialEq for SecretBuf {
+    /// Compare in constant time, as with `ct_eq`.
+    fn eq(&self, other: &Self) -> bool {
+        ct_eq(self, other)
+    }
+}
+impl Eq for SecretBuf {}
+impl core::fmt::Debug for SecretBuf {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("SecretBuf").field("len", &self.len).field("secret", &self.secret).finish_non_exhaustive()
+    }
+}
+impl Drop for SecretBuf {
+    fn drop(&mut self) {
+        // Volatile writes cannot be removed as dead stores, despite the memory being unmapped straight after
+        let data = self.data();
+        for i in 0..self.len {
+            unsafe { ptr::write_volatile(data.add(i), 0) };
+        }
+        compiler_fence(Ordering::SeqCst);
+    }
+}
+
+/// Compare two byte strings in time that depends only on their lengths, not on their contents.
+///
+/// Lengths are not secret, so slices of different lengths compare unequal immediately.
+pub fn ct_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false
+    }
+    let difference = a.iter().zip(b).fold(0u8, |difference, (a, b)| difference | (a ^ b));
+    // Keep the compiler from turning the fold in to an early-exit comparison
+    core::hint::black_box(difference) == 0
+}
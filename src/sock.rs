@@ -118,6 +118,11 @@ pub struct Ancillary<T, const N: usize> {
     ty: AncillaryType,
     data: AncillaryData<T, N>
 }
+impl<T: Copy, const N: usize> Default for Ancillary<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 impl<T: Copy, const N: usize> Ancillary<T, N> {
     pub fn new() -> Self {
         Self {
@@ -132,28 +137,28 @@ impl<T: Copy, const N: usize> Ancillary<T, N> {
         for (i, &data) in data.iter().enumerate() {
             ancillary.0[i].write(data);
         }
-        let header_len = std::mem::size_of::<Ancillary<T, 0>>();
-        let item_len = (std::mem::size_of::<std::mem::MaybeUninit<T>>() - header_len) / N;
         Self {
-            len: header_len + (item_len * data.len()),
+            len: std::mem::size_of::<Ancillary<T, 0>>() + std::mem::size_of_val(data),
             level,
             ty,
             data: ancillary
         }
     }
     pub fn add_item(&mut self, item: T) -> Option<T> {
-        if self.len == std::mem::size_of::<std::mem::MaybeUninit<T>>() {
+        let count = self.items().len();
+        if count == N {
             Some(item)
         } else {
-            self.data.0[(self.len - std::mem::size_of::<Ancillary<T, 0>>()) / std::mem::size_of::<std::mem::MaybeUninit<T>>()].write(item);
-            self.len += (std::mem::size_of::<std::mem::MaybeUninit<T>>() - std::mem::size_of::<Ancillary<T, 0>>()) / N;
+            self.data.0[count].write(item);
+            self.len += std::mem::size_of::<T>();
             None
         }
     }
     /// Get the items from the ancillary data. All items are initialised, but contain an arbitrary bit pattern the may not be
     /// valid for `T`.
     pub fn items(&self) -> &[std::mem::MaybeUninit<T>] {
-        &self.data.0[..(self.len - std::mem::size_of::<Ancillary<T, 0>>()) / std::mem::size_of::<std::mem::MaybeUninit<T>>()]
+        let count = self.len.saturating_sub(std::mem::size_of::<Ancillary<T, 0>>()) / std::mem::size_of::<T>();
+        &self.data.0[..count.min(N)]
     }
     pub fn level(&self) -> Level {
         self.level
@@ -1,15 +1,53 @@
 use core::{arch::asm, marker::PhantomData, fmt::Debug};
-use crate::{enumeration, sock::{self, Ancillary}};
+use crate::{enumeration, exec::Argv, sock::{self, Ancillary}};
+
+mod at;
+pub use at::{AccessMode, AtFlags, RenameFlags, UnlinkFlags};
 
 pub mod epoll;
 
+pub mod clone;
+
+pub mod dirent;
+
+mod fallocate;
+pub use fallocate::FallocateMode;
+
 mod fcntl;
-pub use fcntl::Fcntl;
+pub use fcntl::{Fcntl, Seals};
+
+mod futex;
+pub use futex::FutexFlags;
+
+pub mod inotify;
+
+mod membarrier;
+pub use membarrier::MembarrierCmd;
+
+mod memfd;
+pub use memfd::MemfdFlags;
+
+pub mod mempolicy;
 
 pub mod mmap;
 
+pub mod mount;
+
 mod stat;
-pub use stat::{Device, Stat};
+pub use stat::{Device, FileType, Stat};
+
+pub mod statfs;
+
+mod sync;
+pub use sync::SyncFileRangeFlags;
+
+mod time;
+pub use time::{Clock, Timespec, UTIME_NOW, UTIME_OMIT};
+
+pub mod userfaultfd;
+
+mod xattr;
+pub use xattr::{XattrFlags, XattrNames};
 
 macro_rules! syscall {
     ($num:literal() -> $ret:ident) => {
@@ -435,8 +473,11 @@ impl Fd<'static> {
     pub const stdout: &'static Self = &Self(1, PhantomData);
     #[allow(non_upper_case_globals)]
     pub const stderr: &'static Self = &Self(2, PhantomData);
+    /// The current working directory, for use as the directory of the `*at` family of syscalls.
+    #[allow(non_upper_case_globals)]
+    pub const cwd: &'static Self = &Self(-100i32 as u32, PhantomData);
 }
-impl<'a, 'b> AsRef<Fd<'b>> for Fd<'b> {
+impl<'b> AsRef<Fd<'b>> for Fd<'b> {
     fn as_ref(&self) -> &Fd<'b> {
         self
     }
@@ -465,6 +506,57 @@ impl File {
     pub fn write(&self, buf: &[u8]) -> Result<usize, Error> {
         write(self, buf)
     }
+    /// Change the access mode of the file.
+    #[inline]
+    pub fn chmod(&self, mode: open::Mode) -> Result<(), Error> {
+        fchmod(self, mode)
+    }
+    /// Change the owner and group of the file.
+    /// 
+    /// `None` leaves the respective ID unchanged.
+    #[inline]
+    pub fn chown(&self, uid: Option<u32>, gid: Option<u32>) -> Result<(), Error> {
+        fchown(self, uid, gid)
+    }
+    /// Set the access and modification timestamps of the file.
+    /// 
+    /// `Timespec::NOW` and `Timespec::OMIT` may be used to set a timestamp to the current time or to leave it unchanged.
+    #[inline]
+    pub fn set_times(&self, access: Timespec, modify: Timespec) -> Result<(), Error> {
+        futimens(self, Some(&[access, modify]))
+    }
+    /// Truncate or extend the file to exactly `length` bytes.
+    #[inline]
+    pub fn truncate(&self, length: u64) -> Result<(), Error> {
+        ftruncate(self, length)
+    }
+    /// Flush all modified data and metadata of the file to the storage device.
+    #[inline]
+    pub fn sync(&self) -> Result<(), Error> {
+        fsync(self)
+    }
+    /// Flush modified data of the file to the storage device, along with only the metadata needed to read it back.
+    #[inline]
+    pub fn sync_data(&self) -> Result<(), Error> {
+        fdatasync(self)
+    }
+    /// Manipulate the allocated disk space of the file.
+    #[inline]
+    pub fn allocate(&self, mode: FallocateMode, offset: u64, length: u64) -> Result<(), Error> {
+        fallocate(self, mode, offset, length)
+    }
+    /// Add seals to the file, restricting how it may be modified.
+    /// 
+    /// Fails with `EPERM` if the file does not support sealing or `Seals::SEAL` has been applied.
+    #[inline]
+    pub fn add_seals(&self, seals: Seals) -> Result<(), Error> {
+        fcntl(self, Fcntl::AddSeals(seals)).map(|_| ())
+    }
+    /// Get the seals applied to the file.
+    #[inline]
+    pub fn seals(&self) -> Result<Seals, Error> {
+        fcntl(self, Fcntl::GetSeals).map(Seals)
+    }
     /// Get the un-owned, raw file descriptor
     pub fn fd<'a>(&'a self) -> Fd<'a> {
         Fd(self.0, PhantomData)
@@ -543,6 +635,241 @@ impl Drop for Socket {
     }
 }
 
+/// A handle opened with `open::Flags::PATH`.
+/// 
+/// The handle identifies a location in the file system without granting access to the file's contents, so it
+/// cannot be read or written. It only offers the operations that such a handle supports: getting metadata, opening
+/// files relative to it and changing the working directory to it.
+#[repr(transparent)]
+pub struct PathFd(u32);
+impl PathFd {
+    /// Get the metadata of the file.
+    #[inline]
+    pub fn stat(&self) -> Result<Stat, Error> {
+        fstat(self.fd())
+    }
+    /// Get the metadata of a file relative to this directory.
+    /// 
+    /// With `AtFlags::EMPTY_PATH` and an empty path, this is the file itself.
+    #[inline]
+    pub fn stat_at(&self, path: &core::ffi::CStr, flags: AtFlags) -> Result<Stat, Error> {
+        unsafe { fstatat_unsafe(self.fd(), path.as_ptr() as *const u8, flags) }
+    }
+    /// Open a file relative to this directory.
+    #[inline]
+    pub fn open_at(&self, path: &core::ffi::CStr, flags: open::Flags, mode: open::Mode) -> Result<File, Error> {
+        unsafe { openat_unsafe(self.fd(), path.as_ptr() as *const u8, flags, mode) }
+    }
+    /// Open a location relative to this directory without opening the file's contents.
+    /// 
+    /// `flags` may only contain `CLOSE_ON_EXEC`, `DIRECTORY` and `NO_FOLLOW`, as with `open_path`.
+    #[inline]
+    pub fn open_path_at(&self, path: &core::ffi::CStr, flags: open::Flags) -> Result<PathFd, Error> {
+        if flags.any(!(open::Flags::CLOSE_ON_EXEC | open::Flags::DIRECTORY | open::Flags::NO_FOLLOW)) {
+            return Err(Error::EINVAL)
+        }
+        let open::Flags(flags) = flags | open::Flags::PATH;
+        let fd: isize;
+        unsafe {
+            syscall!{
+                257(self.0, path.as_ptr(), flags, 0) -> fd
+            }
+        }
+        fd.try_into()
+    }
+    /// Make this directory the working directory of the process.
+    #[inline]
+    pub fn chdir(&self) -> Result<(), Error> {
+        fchdir(self.fd())
+    }
+    /// Reading or writing through the descriptor fails with `EBADF`, so it is not exposed.
+    fn fd(&self) -> Fd<'_> {
+        Fd(self.0, PhantomData)
+    }
+}
+impl TryFrom<isize> for PathFd {
+    type Error = Error;
+    fn try_from(maybe: isize) -> Result<Self, Self::Error> {
+        if maybe < 0 {
+            Err(Error(-maybe as u32))
+        } else {
+            Ok(Self(maybe as u32))
+        }
+    }
+}
+impl Debug for PathFd {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("PathFd")
+            .field("fd", &self.0)
+            .finish()
+    }
+}
+impl FileDescriptor for PathFd {
+    #[inline(always)]
+    fn raw(&self) -> u32 {
+        self.0
+    }
+}
+impl Drop for PathFd {
+    fn drop(&mut self) {
+        let _ = close(self.fd());
+    }
+}
+
+/// Options for opening a file, which are validated before the file is opened.
+/// 
+/// Files are opened with `CLOSE_ON_EXEC`. New files are created with mode `0o666`, less the process umask,
+/// unless a mode is given.
+#[derive(Debug, Clone, Copy)]
+pub struct OpenOptions {
+    read: bool,
+    write: bool,
+    append: bool,
+    truncate: bool,
+    create: bool,
+    create_new: bool,
+    temporary: bool,
+    mode: open::Mode,
+    flags: open::Flags
+}
+impl Default for OpenOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl OpenOptions {
+    /// Flags that are controlled by the other options and may not be passed to `custom_flags`.
+    const RESERVED: open::Flags = open::Flags(
+        open::Flags::WRITE_ONLY.0 | open::Flags::READ_WRITE.0 | open::Flags::CREATE.0 | open::Flags::EXCLUSIVE.0
+        | open::Flags::TRUNCATE.0 | open::Flags::APPEND.0 | open::Flags::PATH.0 | (open::Flags::TMPFILE.0 & !open::Flags::DIRECTORY.0)
+    );
+    /// Options with no access requested.
+    pub fn new() -> Self {
+        Self {
+            read: false,
+            write: false,
+            append: false,
+            truncate: false,
+            create: false,
+            create_new: false,
+            temporary: false,
+            mode: open::Mode(0o666),
+            flags: open::Flags::CLOSE_ON_EXEC
+        }
+    }
+    /// Open the file for reading.
+    pub fn read(mut self, read: bool) -> Self {
+        self.read = read;
+        self
+    }
+    /// Open the file for writing.
+    pub fn write(mut self, write: bool) -> Self {
+        self.write = write;
+        self
+    }
+    /// Open the file for writing, with every write going to the end of the file.
+    pub fn append(mut self, append: bool) -> Self {
+        self.append = append;
+        self
+    }
+    /// Truncate an existing file to zero length. Requires `write`.
+    pub fn truncate(mut self, truncate: bool) -> Self {
+        self.truncate = truncate;
+        self
+    }
+    /// Create the file if it does not exist. Requires `write` or `append`.
+    pub fn create(mut self, create: bool) -> Self {
+        self.create = create;
+        self
+    }
+    /// Create the file, failing with `EEXIST` if it already exists. Requires `write` or `append`.
+    /// 
+    /// Symlinks are not followed, so a symlink at the path also fails with `EEXIST`.
+    pub fn create_new(mut self, create_new: bool) -> Self {
+        self.create_new = create_new;
+        self
+    }
+    /// Create an unnamed temporary file in the directory given by the path. Requires `write`.
+    /// 
+    /// The file can later be given a name with `linkat`.
+    pub fn temporary(mut self, temporary: bool) -> Self {
+        self.temporary = temporary;
+        self
+    }
+    /// The mode of a newly created file, before the process umask is applied.
+    pub fn mode(mut self, mode: open::Mode) -> Self {
+        self.mode = mode;
+        self
+    }
+    /// Additional flags, such as `NON_BLOCKING`, `NO_FOLLOW` or `SYNC`, replacing the default `CLOSE_ON_EXEC`.
+    /// 
+    /// Flags controlled by the other options are rejected with `EINVAL` when opening.
+    pub fn custom_flags(mut self, flags: open::Flags) -> Self {
+        self.flags = flags;
+        self
+    }
+    /// The flags that will be passed to `open`.
+    /// 
+    /// Returns `EINVAL` for invalid combinations of options, such as `truncate` without `write`,
+    /// or requesting neither read nor write access.
+    pub fn flags(&self) -> Result<open::Flags, Error> {
+        if self.flags.any(Self::RESERVED) {
+            return Err(Error::EINVAL)
+        }
+        let writable = self.write || self.append;
+        let mut flags = self.flags | match (self.read, writable) {
+            (true, false) => open::Flags::READ_ONLY,
+            (false, true) => open::Flags::WRITE_ONLY,
+            (true, true) => open::Flags::READ_WRITE,
+            (false, false) => return Err(Error::EINVAL)
+        };
+        if self.append {
+            flags |= open::Flags::APPEND;
+        }
+        if self.truncate {
+            if !self.write || self.append {
+                return Err(Error::EINVAL)
+            }
+            flags |= open::Flags::TRUNCATE;
+        }
+        if self.create_new {
+            flags |= open::Flags::CREATE | open::Flags::EXCLUSIVE;
+        } else if self.create {
+            flags |= open::Flags::CREATE;
+        }
+        if (self.create || self.create_new) && !writable {
+            return Err(Error::EINVAL)
+        }
+        if self.temporary {
+            if !self.write || self.append || self.truncate || self.create || self.create_new {
+                return Err(Error::EINVAL)
+            }
+            flags |= open::Flags::TMPFILE;
+        }
+        Ok(flags)
+    }
+    /// The mode that will be passed to `open`.
+    fn creation_mode(&self) -> open::Mode {
+        if self.create || self.create_new || self.temporary {
+            self.mode
+        } else {
+            open::Mode::NONE
+        }
+    }
+    /// Open a file with the options.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open<P: AsRef<std::path::Path>>(&self, path: P) -> Result<File, Error> {
+        open(path, self.flags()?, self.creation_mode())
+    }
+    /// Open a file relative to a directory with the options.
+    #[inline]
+    #[cfg(feature = "std")]
+    pub fn open_at<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(&self, dir: D, path: P) -> Result<File, Error> {
+        openat(dir, path, self.flags()?, self.creation_mode())
+    }
+}
+
 #[derive(Debug)]
 #[repr(C)]
 pub struct IoVec<'a> {
@@ -620,6 +947,20 @@ impl<'a> From<&'a mut [u8]> for IoVecMut<'a> {
     }
 }
 
+/// A region of memory in another process.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct RemoteIoVec {
+    pub address: usize,
+    pub length: usize
+}
+impl RemoteIoVec {
+    #[inline(always)]
+    pub fn new(address: usize, length: usize) -> Self {
+        Self { address, length }
+    }
+}
+
 /// Read in the next available bytes from a file.
 /// The buffer may be uninitialised.
 /// 
@@ -661,10 +1002,21 @@ pub mod open {
             WRITE_ONLY = 0b01,
             READ_WRITE = 0b10,
             CREATE = 0o100,
+            EXCLUSIVE = 0o200,
+            NO_CONTROLLING_TERMINAL = 0o400,
+            TRUNCATE = 0o1000,
             APPEND = 0o2000,
             NON_BLOCKING = 0o4000,
+            DATA_SYNC = 0o10000,
+            DIRECT = 0o40000,
+            LARGE_FILE = 0o100000,
             CLOSE_ON_EXEC = 0o2000000,
-            NO_ACCESS_TIME = 0o1000000
+            NO_ACCESS_TIME = 0o1000000,
+            DIRECTORY = 0o200000,
+            NO_FOLLOW = 0o400000,
+            SYNC = 0o4010000,
+            PATH = 0o10000000,
+            TMPFILE = 0o20200000
         } _ => Err(Error::EINVAL)
     }
     #[derive(Clone, Copy, PartialEq, Eq)]
@@ -800,6 +1152,37 @@ pub fn open<P: AsRef<std::path::Path>>(path: P, flags: open::Flags, mode: open::
     unsafe { open_unsafe(path.as_ptr() as *const u8, flags, mode) }
 }
 
+/// Open a location in the file system without opening the file's contents.
+/// 
+/// `flags` may only contain `CLOSE_ON_EXEC`, `DIRECTORY` and `NO_FOLLOW`, otherwise `EINVAL` is returned.
+/// With `NO_FOLLOW`, a final symlink is opened itself.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn open_path_unsafe(path: *const u8, flags: open::Flags) -> Result<PathFd, Error> {
+    if flags.any(!(open::Flags::CLOSE_ON_EXEC | open::Flags::DIRECTORY | open::Flags::NO_FOLLOW)) {
+        return Err(Error::EINVAL)
+    }
+    let open::Flags(flags) = flags | open::Flags::PATH;
+    let fd: isize;
+    syscall!{
+        0x02(path, flags, 0) -> fd
+    }
+    fd.try_into()
+}
+/// Open a location in the file system without opening the file's contents.
+/// 
+/// `flags` may only contain `CLOSE_ON_EXEC`, `DIRECTORY` and `NO_FOLLOW`, otherwise `EINVAL` is returned.
+/// With `NO_FOLLOW`, a final symlink is opened itself.
+#[inline]
+#[cfg(feature = "std")]
+pub fn open_path<P: AsRef<std::path::Path>>(path: P, flags: open::Flags) -> Result<PathFd, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { open_path_unsafe(path.as_ptr() as *const u8, flags) }
+}
+
 #[inline]
 pub fn close<'a, F: AsRef<Fd<'a>>>(fd: F) -> Result<(), Error> {
     let err;
@@ -864,17 +1247,61 @@ pub fn lstat<P: AsRef<std::path::Path>>(path: P) -> Result<Stat, Error> {
 
 /// Map a memory object in to the processes address space.
 /// 
-/// # Safety
+/// `offset` must be a multiple of `mmap::PAGE_SIZE`, otherwise `EINVAL` is returned. The size of huge pages is selected
+/// with one of the `HUGE_*` flags alongside `HUGETLB`. `FIXED` would replace any existing mapping, so `EINVAL` is
+/// returned if it is set; use `mmap_unsafe` to place a mapping over memory that is already mapped.
+/// 
 /// Though creating a memory mapping can be considered safe, use of the memory mapping is likely quite unsafe.
 /// Extra care must be taken when using a shared memory mapping.
 #[inline]
 pub fn mmap<'a, F: AsRef<Fd<'a>>>(address: usize, length: usize, protection: mmap::Protection, flags: mmap::Flags, fd: F, offset: usize) -> Result<*mut core::ffi::c_void, Error> {
+    if flags.any(mmap::Flags::FIXED) {
+        return Err(Error::EINVAL)
+    }
+    unsafe { mmap_unsafe(address, length, protection, flags, fd, offset) }
+}
+/// Map a memory object in to the processes address space, allowing `FIXED`.
+/// 
+/// `offset` must be a multiple of `mmap::PAGE_SIZE`, otherwise `EINVAL` is returned.
+/// 
+/// # Safety
+/// With `FIXED`, any existing mapping in the range is replaced. The range must not contain memory that is in use, or
+/// that is pointed to by a reference.
+#[inline]
+pub unsafe fn mmap_unsafe<'a, F: AsRef<Fd<'a>>>(address: usize, length: usize, protection: mmap::Protection, flags: mmap::Flags, fd: F, offset: usize) -> Result<*mut core::ffi::c_void, Error> {
+    if !offset.is_multiple_of(mmap::PAGE_SIZE) {
+        return Err(Error::EINVAL)
+    }
+    mmap_raw(address, length, protection, flags, fd.as_ref().raw(), offset)
+}
+/// Map zeroed memory that is not backed by a file in to the processes address space.
+/// 
+/// `ANONYMOUS` is added to `flags`, which must also contain one of `SHARED` or `PRIVATE`. As with `mmap`, `EINVAL` is
+/// returned if `FIXED` is set; use `mmap_anonymous_unsafe` to replace an existing mapping.
+#[inline]
+pub fn mmap_anonymous(address: usize, length: usize, protection: mmap::Protection, flags: mmap::Flags) -> Result<*mut core::ffi::c_void, Error> {
+    if flags.any(mmap::Flags::FIXED) {
+        return Err(Error::EINVAL)
+    }
+    unsafe { mmap_anonymous_unsafe(address, length, protection, flags) }
+}
+/// Map zeroed memory that is not backed by a file in to the processes address space, allowing `FIXED`.
+/// 
+/// # Safety
+/// With `FIXED`, any existing mapping in the range is replaced. The range must not contain memory that is in use, or
+/// that is pointed to by a reference.
+#[inline]
+pub unsafe fn mmap_anonymous_unsafe(address: usize, length: usize, protection: mmap::Protection, flags: mmap::Flags) -> Result<*mut core::ffi::c_void, Error> {
+    mmap_raw(address, length, protection, flags | mmap::Flags::ANONYMOUS, -1i32 as u32, 0)
+}
+#[inline]
+fn mmap_raw(address: usize, length: usize, protection: mmap::Protection, flags: mmap::Flags, fd: u32, offset: usize) -> Result<*mut core::ffi::c_void, Error> {
     let ptr;
     let flags: u32 = flags.into();
     let protection: u32 = protection.into();
     unsafe {
         syscall!{
-            9(address, length, protection, flags, fd.as_ref().raw(), offset) -> ptr
+            9(address, length, protection, flags, fd, offset) -> ptr
         }
     }
     Error::maybe_ptr(ptr)
@@ -886,8 +1313,9 @@ pub fn mmap<'a, F: AsRef<Fd<'a>>>(address: usize, length: usize, protection: mma
 /// # Safety
 /// Changing the memory protection for a region pointed to by a reference is undefined behaviour.
 #[inline]
-pub unsafe fn mprotect(address: *mut core::ffi::c_void, length: usize, protection: usize) -> Result<(), Error> {
+pub unsafe fn mprotect(address: *mut core::ffi::c_void, length: usize, protection: mmap::Protection) -> Result<(), Error> {
     let err;
+    let protection: u32 = protection.into();
     syscall!{
         10(address, length, protection) -> err
     }
@@ -934,19 +1362,97 @@ pub fn readv<'a, F: AsRef<Fd<'a>>>(fd: F, iov: &[IoVec]) -> Result<usize, Error>
     Error::maybe_usize(count)
 }
 
+/// Check whether the process can access a file, using its real user and group IDs.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn access_unsafe(path: *const u8, mode: AccessMode) -> Result<(), Error> {
+    let maybe: isize;
+    let mode: u32 = mode.into();
+    syscall!{
+        21(path, mode) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Check whether the process can access a file, using its real user and group IDs.
+#[inline]
+#[cfg(feature = "std")]
+pub fn access<P: AsRef<std::path::Path>>(path: P, mode: AccessMode) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { access_unsafe(path.as_ptr() as *const u8, mode) }
+}
+
 /// Remap an existing memory mapping.
 /// 
+/// `new_address` is only used with `FIXED`, which also requires `MAY_MOVE`.
+/// 
 /// # Safety
 /// Remapping memory that is in use or is pointed to by a reference is undefined behaviour if `MAY_MOVE` is specified or if the mapping is shrunk.
+/// With `FIXED`, any existing mapping at `new_address` is replaced.
 #[inline]
-pub unsafe fn mremap(old_address: *mut core::ffi::c_void, old_size: usize, new_size: usize, flags: mmap::RemapFlags) -> Result<*mut core::ffi::c_void, Error> {
+pub unsafe fn mremap(old_address: *mut core::ffi::c_void, old_size: usize, new_size: usize, flags: mmap::RemapFlags, new_address: *mut core::ffi::c_void) -> Result<*mut core::ffi::c_void, Error> {
     let maybe;
     let flags: u32 = flags.into();
     syscall!{
-        25(old_address, old_size, new_size, flags) -> maybe
+        25(old_address, old_size, new_size, flags, new_address) -> maybe
     }
     Error::maybe_ptr(maybe)
 }
+/// Flush changes to a shared file mapping back to the file.
+#[inline]
+pub fn msync(address: *mut core::ffi::c_void, length: usize, flags: mmap::MsyncFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            26(address, length, flags) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Get which pages of a region of memory are resident, one byte per page with the lowest bit set if it is resident.
+/// 
+/// `address` must be page-aligned. Returns the filled portion of `residency`, or `ERANGE` if it is too small.
+#[inline]
+pub fn mincore(address: *mut core::ffi::c_void, length: usize, residency: &mut [u8]) -> Result<&[u8], Error> {
+    let pages = length.div_ceil(mmap::PAGE_SIZE);
+    let residency = residency.get_mut(..pages).ok_or(Error::ERANGE)?;
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            27(address, length, residency.as_mut_ptr()) -> maybe
+        }
+    }
+    Error::maybe(maybe).map(|_| &*residency)
+}
+/// Give the kernel advice about how a region of memory will be used.
+/// 
+/// # Safety
+/// Advice such as `DONT_NEED`, `FREE` and `REMOVE` discards the contents of the memory, which must not be in use
+/// or pointed to by a reference.
+#[inline]
+pub unsafe fn madvise(address: *mut core::ffi::c_void, length: usize, advice: mmap::Advice) -> Result<(), Error> {
+    let maybe: isize;
+    let advice: u32 = advice.into();
+    syscall!{
+        28(address, length, advice) -> maybe
+    }
+    Error::maybe(maybe)
+}
+
+/// Get the ID of the process.
+#[inline]
+pub fn getpid() -> u32 {
+    let pid: isize;
+    unsafe {
+        syscall!{
+            39() -> pid
+        }
+    }
+    pid as u32
+}
 
 /// Create a socket file descriptor.
 #[inline]
@@ -1076,36 +1582,190 @@ pub fn listen<'a, F: AsRef<Fd<'a>>>(socket: F, backlog: u32) -> Result<(), Error
     Error::maybe(maybe)
 }
 
-/// Terminate the process, returning a code to the parent process.
-/// 
-/// Linux will clean up used resources, however, language termination functions such as `Drop` will not be run.
+/// Create a pair of connected sockets, such as for passing file descriptors to a child process.
 #[inline]
-pub fn exit(code: i32) -> ! {
+pub fn socketpair(domain: sock::Domain, ty: sock::Type, protocol: sock::Protocol) -> Result<(Socket, Socket), Error> {
+    let maybe: isize;
+    let mut fds = [0u32; 2];
+    let domain: u32 = domain.into();
+    let ty: u32 = ty.into();
+    let protocol: u32 = protocol.into();
     unsafe {
-        asm!(
-            "mov rax, 60",
-            "syscall",
-            in("rdi") code,
-            options(noreturn)
-        )
+        syscall!{
+            53(domain, ty, protocol, fds.as_mut_ptr()) -> maybe
+        }
     }
+    Error::maybe(maybe).map(|_| (Socket(fds[0]), Socket(fds[1])))
 }
-
-/// Manipulate a file descriptor.
+/// Create a child process as with `clone3`, through the legacy `clone`.
+#[inline]
+unsafe fn clone_legacy(args: &clone::CloneArgs) -> Result<clone::Fork, Error> {
+    if !args.is_legacy() {
+        return Err(Error::ENOSYS)
+    }
+    let pid: isize;
+    let mut pidfd = 0u32;
+    // The pidfd is returned through the parent TID pointer
+    syscall!{
+        56(args.legacy_flags(), 0, &mut pidfd as *mut u32, 0, 0) -> pid
+    }
+    let pid = Error::maybe_u32(pid)?;
+    Ok(clone::Fork::new(pid, args.get_flags().any(clone::Flags::PIDFD).then_some(pidfd)))
+}
+/// Create a child process with a copy of the memory and file descriptors of the calling process.
 /// 
 /// # Safety
-/// - `arg` must be appropriate for the given command and device of the file descriptor.
-/// - `*mut T` must be a thin pointer.
+/// Only the calling thread is copied, so locks held by other threads stay held in the child. In a multi-threaded
+/// process the child must only make syscalls, and must not allocate, until it calls `execve` or `exit`.
 #[inline]
-pub fn fcntl<'a, F: AsRef<Fd<'a>>>(fd: F, cmd: Fcntl) -> Result<u32, Error> {
-    let maybe;
-    unsafe {
-        if let Some(arg) = cmd.arg() {
-            syscall!{
-                72(fd.as_ref().raw(), cmd.cmd(), arg) -> maybe
-            }
-        } else {
-            syscall!{
+pub unsafe fn fork() -> Result<clone::Fork, Error> {
+    let pid: isize;
+    syscall!{
+        57() -> pid
+    }
+    Error::maybe_u32(pid).map(|pid| clone::Fork::new(pid, None))
+}
+/// Spawn a child that shares the memory of the calling process, running `child` on `stack`.
+/// 
+/// The calling thread is suspended until the child calls `execve` or exits, avoiding the cost of copying the address
+/// space. If `child` returns, the child exits with the returned code. Returns the PID of the child.
+/// 
+/// # Safety
+/// The child runs in the address space of the caller, with the thread-local storage of the calling thread. It must
+/// only make syscalls, and must not allocate or unwind. `stack` must be large enough for `child`.
+pub unsafe fn vfork<F: FnOnce() -> i32>(stack: &mut [u8], child: F) -> Result<u32, Error> {
+    extern "C" fn trampoline<F: FnOnce() -> i32>(child: *mut F) -> ! {
+        exit(unsafe { core::ptr::read(child)() })
+    }
+    let entry: extern "C" fn(*mut F) -> ! = trampoline::<F>;
+    let mut child = core::mem::ManuallyDrop::new(child);
+    let bottom = stack.as_mut_ptr() as usize;
+    let top = (bottom + stack.len()) & !15;
+    let flags = clone::Flags::VM | clone::Flags::VFORK;
+    let args = clone::RawCloneArgs {
+        flags: flags.into(),
+        exit_signal: clone::SIGCHLD as u64,
+        stack: bottom as u64,
+        stack_size: (top - bottom) as u64,
+        ..Default::default()
+    };
+    // The child starts on the new stack and immediately calls the trampoline, so never runs Rust code on the
+    // caller's stack
+    let mut pid: isize;
+    asm!(
+        "syscall",
+        "test rax, rax",
+        "jnz 2f",
+        "xor ebp, ebp",
+        "mov rdi, r12",
+        "call r13",
+        "ud2",
+        "2:",
+        inout("rax") 435isize => pid,
+        in("rdi") &args as *const clone::RawCloneArgs,
+        in("rsi") core::mem::size_of::<clone::RawCloneArgs>(),
+        in("r12") &mut *child as *mut F,
+        in("r13") entry,
+        out("rcx") _,
+        out("r11") _
+    );
+    if pid == -(Error::ENOSYS.0 as isize) {
+        asm!(
+            "syscall",
+            "test rax, rax",
+            "jnz 2f",
+            "xor ebp, ebp",
+            "mov rdi, r12",
+            "call r13",
+            "ud2",
+            "2:",
+            inout("rax") 56isize => pid,
+            in("rdi") u64::from(flags) | clone::SIGCHLD as u64,
+            in("rsi") top,
+            in("rdx") 0,
+            in("r10") 0,
+            in("r8") 0,
+            in("r12") &mut *child as *mut F,
+            in("r13") entry,
+            out("rcx") _,
+            out("r11") _
+        );
+    }
+    let pid = Error::maybe_u32(pid);
+    if pid.is_err() {
+        // The child never ran, so still owns nothing
+        core::mem::ManuallyDrop::drop(&mut child);
+    }
+    pid
+}
+
+/// Replace the program of the calling process.
+/// 
+/// Only returns on failure, with the error. `argv` and `envp` are null-terminated arrays of null-terminated strings.
+/// 
+/// # Safety
+/// `path` must be null-terminated, and `argv` and `envp` must be null-terminated arrays of null-terminated strings.
+#[inline]
+pub unsafe fn execve_unsafe(path: *const u8, argv: *const *const u8, envp: *const *const u8) -> Error {
+    let ret: isize;
+    syscall!{
+        59(path, argv, envp) -> ret
+    }
+    Error(-ret as u32)
+}
+/// Replace the program of the calling process, with arguments and environment built by `exec::Arena`.
+/// 
+/// Only returns on failure, with the error. Nothing is allocated, so this may be called in the child of `vfork`.
+#[inline]
+pub fn execve(path: &core::ffi::CStr, argv: &Argv, envp: &Argv) -> Error {
+    unsafe { execve_unsafe(path.as_ptr() as *const u8, argv.as_ptr(), envp.as_ptr()) }
+}
+/// Terminate the process, returning a code to the parent process.
+/// 
+/// Linux will clean up used resources, however, language termination functions such as `Drop` will not be run.
+#[inline]
+pub fn exit(code: i32) -> ! {
+    unsafe {
+        asm!(
+            "mov rax, 60",
+            "syscall",
+            in("rdi") code,
+            options(noreturn)
+        )
+    }
+}
+/// Wait for a child to change state, returning its PID and status.
+/// 
+/// A `pid` of -1 waits for any child, 0 for any child in the process group of the caller, and less than -1 for any
+/// child in the process group `-pid`. With `WaitFlags::NO_HANG` a PID of zero is returned if no child has changed state.
+#[inline]
+pub fn wait4(pid: i32, flags: clone::WaitFlags) -> Result<(u32, clone::WaitStatus), Error> {
+    let maybe: isize;
+    let mut status = 0i32;
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            61(pid, &mut status as *mut i32, flags, 0) -> maybe
+        }
+    }
+    Error::maybe_u32(maybe).map(|pid| (pid, clone::WaitStatus(status)))
+}
+
+/// Manipulate a file descriptor.
+/// 
+/// # Safety
+/// - `arg` must be appropriate for the given command and device of the file descriptor.
+/// - `*mut T` must be a thin pointer.
+#[inline]
+pub fn fcntl<'a, F: AsRef<Fd<'a>>>(fd: F, cmd: Fcntl) -> Result<u32, Error> {
+    let maybe;
+    unsafe {
+        if let Some(arg) = cmd.arg() {
+            syscall!{
+                72(fd.as_ref().raw(), cmd.cmd(), arg) -> maybe
+            }
+        } else {
+            syscall!{
                 72(fd.as_ref().raw(), cmd.cmd()) -> maybe
             }
         }
@@ -1113,6 +1773,73 @@ pub fn fcntl<'a, F: AsRef<Fd<'a>>>(fd: F, cmd: Fcntl) -> Result<u32, Error> {
     Error::maybe_u32(maybe)
 }
 
+/// Flush all modified data and metadata of an open file to the storage device.
+#[inline]
+pub fn fsync<'a, F: AsRef<Fd<'a>>>(fd: F) -> Result<(), Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            74(fd.as_ref().raw()) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Flush modified data of an open file to the storage device, along with only the metadata needed to read it back.
+#[inline]
+pub fn fdatasync<'a, F: AsRef<Fd<'a>>>(fd: F) -> Result<(), Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            75(fd.as_ref().raw()) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+
+/// Truncate or extend a file to exactly `length` bytes.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn truncate_unsafe(path: *const u8, length: u64) -> Result<(), Error> {
+    let maybe: isize;
+    syscall!{
+        76(path, length) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Truncate or extend a file to exactly `length` bytes.
+#[inline]
+#[cfg(feature = "std")]
+pub fn truncate<P: AsRef<std::path::Path>>(path: P, length: u64) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { truncate_unsafe(path.as_ptr() as *const u8, length) }
+}
+/// Truncate or extend an open file to exactly `length` bytes.
+#[inline]
+pub fn ftruncate<'a, F: AsRef<Fd<'a>>>(fd: F, length: u64) -> Result<(), Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            77(fd.as_ref().raw(), length) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+
+/// Change the working directory of the process to an open directory.
+#[inline]
+pub fn fchdir<'a, F: AsRef<Fd<'a>>>(fd: F) -> Result<(), Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            81(fd.as_ref().raw()) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+
 /// Remove an entry from the file system.
 /// 
 /// # Safety
@@ -1134,57 +1861,1594 @@ pub fn unlink<P: AsRef<std::path::Path>>(path: P) -> Result<(), Error> {
     unsafe { unlink_unsafe(path.as_ptr() as *const u8) }
 }
 
-/// Wait for an entry to enter the epoll ready list.
+/// Change the access mode of an open file.
 #[inline]
-pub fn epoll_wait<'a, 'b, E: AsRef<Fd<'b>>>(epoll: E, events: &'a mut [std::mem::MaybeUninit<epoll::Event>], timeout: u32) -> Result<&'a [epoll::Event], Error> {
+pub fn fchmod<'a, F: AsRef<Fd<'a>>>(fd: F, open::Mode(mode): open::Mode) -> Result<(), Error> {
     let maybe: isize;
     unsafe {
         syscall!{
-            232(epoll.as_ref().raw(), events.as_mut_ptr(), events.len(), timeout) -> maybe
+            91(fd.as_ref().raw(), mode) -> maybe
         }
     }
-    Error::maybe_u32(maybe).map(|count| unsafe { std::mem::transmute(&events[..count as usize]) })
+    Error::maybe(maybe)
 }
-/// Modify an entry in the epoll wait list.
+/// Change the owner and group of an open file.
+/// 
+/// `None` leaves the respective ID unchanged.
 #[inline]
-pub fn epoll_ctl<'a, 'b, E: AsRef<Fd<'a>>, F: AsRef<Fd<'b>>>(epoll: E, fd: F, cmd: epoll::Cntl) -> Result<(), Error> {
+pub fn fchown<'a, F: AsRef<Fd<'a>>>(fd: F, uid: Option<u32>, gid: Option<u32>) -> Result<(), Error> {
     let maybe: isize;
     unsafe {
         syscall!{
-            233(epoll.as_ref().raw(), cmd.cmd(), fd.as_ref().raw(), cmd.arg().map(|p| p as *const epoll::Event).unwrap_or(core::ptr::null())) -> maybe
+            93(fd.as_ref().raw(), uid.unwrap_or(u32::MAX), gid.unwrap_or(u32::MAX)) -> maybe
         }
     }
     Error::maybe(maybe)
 }
-/// Open an epoll file descriptor.
+/// Change the owner and group of a file without following symlinks.
+/// 
+/// `None` leaves the respective ID unchanged.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
 #[inline]
-pub fn epoll_create(flags: epoll::Flags) -> Result<File, Error> {
+pub unsafe fn lchown_unsafe(path: *const u8, uid: Option<u32>, gid: Option<u32>) -> Result<(), Error> {
     let maybe: isize;
-    let flags : u32 = flags.into();
+    syscall!{
+        94(path, uid.unwrap_or(u32::MAX), gid.unwrap_or(u32::MAX)) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Change the owner and group of a file without following symlinks.
+/// 
+/// `None` leaves the respective ID unchanged.
+#[inline]
+#[cfg(feature = "std")]
+pub fn lchown<P: AsRef<std::path::Path>>(path: P, uid: Option<u32>, gid: Option<u32>) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { lchown_unsafe(path.as_ptr() as *const u8, uid, gid) }
+}
+
+/// Get the real user ID of the process.
+#[inline]
+pub fn getuid() -> u32 {
+    let uid: isize;
     unsafe {
         syscall!{
-            291(flags) -> maybe
+            102() -> uid
         }
     }
-    maybe.try_into()
+    uid as u32
+}
+/// Get the real group ID of the process.
+#[inline]
+pub fn getgid() -> u32 {
+    let gid: isize;
+    unsafe {
+        syscall!{
+            104() -> gid
+        }
+    }
+    gid as u32
+}
+/// Get the effective user ID of the process.
+#[inline]
+pub fn geteuid() -> u32 {
+    let uid: isize;
+    unsafe {
+        syscall!{
+            107() -> uid
+        }
+    }
+    uid as u32
+}
+/// Get the effective group ID of the process.
+#[inline]
+pub fn getegid() -> u32 {
+    let gid: isize;
+    unsafe {
+        syscall!{
+            108() -> gid
+        }
+    }
+    gid as u32
 }
 
-/// Create an anonymous file.
+/// Get information about the file system containing a file.
 /// 
 /// # Safety
-/// `name` must be null-terminated.
+/// `path` must be null-terminated.
 #[inline]
-pub unsafe fn memfd_create_unsafe(name: *const u8, flags: u32) -> Result<File, Error> {
-    let fd: isize;
+pub unsafe fn statfs_unsafe(path: *const u8) -> Result<statfs::StatFs, Error> {
+    let mut stat = core::mem::MaybeUninit::uninit();
+    let err;
     syscall!{
-        319(name, flags) -> fd
+        137(path, stat.as_mut_ptr()) -> err
     }
-    fd.try_into()
+    Error::maybe(err).map(|_| stat.assume_init())
 }
-/// Create an anonymous file.
+/// Get information about the file system containing a file.
+#[cfg(feature = "std")]
+#[inline]
+pub fn statfs<P: AsRef<std::path::Path>>(path: P) -> Result<statfs::StatFs, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { statfs_unsafe(path.as_ptr() as *const u8) }
+}
+/// Get information about the file system containing an open file.
+#[inline]
+pub fn fstatfs<'a, F: AsRef<Fd<'a>>>(fd: F) -> Result<statfs::StatFs, Error> {
+    let mut stat = core::mem::MaybeUninit::uninit();
+    let err;
+    unsafe {
+        syscall!{
+            138(fd.as_ref().raw(), stat.as_mut_ptr()) -> err
+        }
+        Error::maybe(err).map(|_| stat.assume_init())
+    }
+}
+
+/// Lock a region of memory in to RAM, preventing it from being paged out.
+#[inline]
+pub fn mlock(address: *const core::ffi::c_void, length: usize) -> Result<(), Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            149(address, length) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Allow a region of memory to be paged out again.
+#[inline]
+pub fn munlock(address: *const core::ffi::c_void, length: usize) -> Result<(), Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            150(address, length) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Lock all current or future mappings of the process in to RAM.
+#[inline]
+pub fn mlockall(flags: mmap::LockAllFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            151(flags) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Allow all mappings of the process to be paged out again.
+#[inline]
+pub fn munlockall() -> Result<(), Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            152() -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+
+/// Attach a file system, or change the properties of an existing mount.
+/// 
+/// `source`, `fs_type` and `data` may be null when unused by the operation selected by `flags`.
+/// 
+/// # Safety
+/// `source`, `target`, `fs_type` and `data` must be null or null-terminated.
+#[inline]
+pub unsafe fn mount_unsafe(source: *const u8, target: *const u8, fs_type: *const u8, flags: mount::MountFlags, data: *const u8) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u64 = flags.into();
+    syscall!{
+        165(source, target, fs_type, flags, data) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Attach a file system, or change the properties of an existing mount.
+/// 
+/// `source`, `fs_type` and `data` may be `None` when unused by the operation selected by `flags`.
+/// For example, changing propagation needs only `target` and one of `PRIVATE`, `SLAVE`, `SHARED` or `UNBINDABLE`.
 #[inline]
 #[cfg(feature = "std")]
-pub fn memfd_create<P: AsRef<std::path::Path>>(name: &str, flags: u32) -> Result<File, Error> {
-    let name = std::ffi::CString::new(name.as_bytes()).map_err(|_| Error::EINVAL)?;
-    unsafe { memfd_create_unsafe(name.as_ptr() as *const u8, flags) }
-}
\ No newline at end of file
+pub fn mount<P: AsRef<std::path::Path>>(source: Option<&std::path::Path>, target: P, fs_type: Option<&str>, flags: mount::MountFlags, data: Option<&str>) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let source = source.map(|source| std::ffi::CString::new(source.as_os_str().as_bytes())).transpose().map_err(|_| Error::EINVAL)?;
+    let target = std::ffi::CString::new(target.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let fs_type = fs_type.map(std::ffi::CString::new).transpose().map_err(|_| Error::EINVAL)?;
+    let data = data.map(std::ffi::CString::new).transpose().map_err(|_| Error::EINVAL)?;
+    let ptr = |s: &Option<std::ffi::CString>| s.as_ref().map(|s| s.as_ptr() as *const u8).unwrap_or(core::ptr::null());
+    unsafe { mount_unsafe(ptr(&source), target.as_ptr() as *const u8, ptr(&fs_type), flags, ptr(&data)) }
+}
+/// Detach a mount.
+/// 
+/// # Safety
+/// `target` must be null-terminated.
+#[inline]
+pub unsafe fn umount2_unsafe(target: *const u8, flags: mount::UnmountFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        166(target, flags) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Detach a mount.
+#[inline]
+#[cfg(feature = "std")]
+pub fn umount2<P: AsRef<std::path::Path>>(target: P, flags: mount::UnmountFlags) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let target = std::ffi::CString::new(target.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { umount2_unsafe(target.as_ptr() as *const u8, flags) }
+}
+
+/// Set the value of an extended attribute of a file.
+/// 
+/// # Safety
+/// `path` and `name` must be null-terminated.
+#[inline]
+pub unsafe fn setxattr_unsafe(path: *const u8, name: *const u8, value: &[u8], flags: XattrFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        188(path, name, value.as_ptr(), value.len(), flags) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Set the value of an extended attribute of a file.
+#[inline]
+#[cfg(feature = "std")]
+pub fn setxattr<P: AsRef<std::path::Path>>(path: P, name: &str, value: &[u8], flags: XattrFlags) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let name = std::ffi::CString::new(name).map_err(|_| Error::EINVAL)?;
+    unsafe { setxattr_unsafe(path.as_ptr() as *const u8, name.as_ptr() as *const u8, value, flags) }
+}
+/// Set the value of an extended attribute of a file without following symlinks.
+/// 
+/// # Safety
+/// `path` and `name` must be null-terminated.
+#[inline]
+pub unsafe fn lsetxattr_unsafe(path: *const u8, name: *const u8, value: &[u8], flags: XattrFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        189(path, name, value.as_ptr(), value.len(), flags) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Set the value of an extended attribute of a file without following symlinks.
+#[inline]
+#[cfg(feature = "std")]
+pub fn lsetxattr<P: AsRef<std::path::Path>>(path: P, name: &str, value: &[u8], flags: XattrFlags) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let name = std::ffi::CString::new(name).map_err(|_| Error::EINVAL)?;
+    unsafe { lsetxattr_unsafe(path.as_ptr() as *const u8, name.as_ptr() as *const u8, value, flags) }
+}
+/// Set the value of an extended attribute of an open file.
+/// 
+/// # Safety
+/// `name` must be null-terminated.
+#[inline]
+pub unsafe fn fsetxattr_unsafe<'a, F: AsRef<Fd<'a>>>(fd: F, name: *const u8, value: &[u8], flags: XattrFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        190(fd.as_ref().raw(), name, value.as_ptr(), value.len(), flags) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Set the value of an extended attribute of an open file.
+#[inline]
+#[cfg(feature = "std")]
+pub fn fsetxattr<'a, F: AsRef<Fd<'a>>>(fd: F, name: &str, value: &[u8], flags: XattrFlags) -> Result<(), Error> {
+    let name = std::ffi::CString::new(name).map_err(|_| Error::EINVAL)?;
+    unsafe { fsetxattr_unsafe(fd, name.as_ptr() as *const u8, value, flags) }
+}
+/// Get the value of an extended attribute of a file.
+/// 
+/// If `value` is empty the size of the value is returned without reading it.
+/// Returns `ERANGE` if `value` is too small.
+/// 
+/// # Safety
+/// `path` and `name` must be null-terminated.
+#[inline]
+pub unsafe fn getxattr_unsafe(path: *const u8, name: *const u8, value: &mut [u8]) -> Result<usize, Error> {
+    let maybe: isize;
+    syscall!{
+        191(path, name, value.as_mut_ptr(), value.len()) -> maybe
+    }
+    Error::maybe_usize(maybe)
+}
+/// Get the value of an extended attribute of a file.
+/// 
+/// If `value` is empty the size of the value is returned without reading it.
+/// Returns `ERANGE` if `value` is too small.
+#[inline]
+#[cfg(feature = "std")]
+pub fn getxattr<P: AsRef<std::path::Path>>(path: P, name: &str, value: &mut [u8]) -> Result<usize, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let name = std::ffi::CString::new(name).map_err(|_| Error::EINVAL)?;
+    unsafe { getxattr_unsafe(path.as_ptr() as *const u8, name.as_ptr() as *const u8, value) }
+}
+/// Get the value of an extended attribute of a file without following symlinks.
+/// 
+/// If `value` is empty the size of the value is returned without reading it.
+/// Returns `ERANGE` if `value` is too small.
+/// 
+/// # Safety
+/// `path` and `name` must be null-terminated.
+#[inline]
+pub unsafe fn lgetxattr_unsafe(path: *const u8, name: *const u8, value: &mut [u8]) -> Result<usize, Error> {
+    let maybe: isize;
+    syscall!{
+        192(path, name, value.as_mut_ptr(), value.len()) -> maybe
+    }
+    Error::maybe_usize(maybe)
+}
+/// Get the value of an extended attribute of a file without following symlinks.
+/// 
+/// If `value` is empty the size of the value is returned without reading it.
+/// Returns `ERANGE` if `value` is too small.
+#[inline]
+#[cfg(feature = "std")]
+pub fn lgetxattr<P: AsRef<std::path::Path>>(path: P, name: &str, value: &mut [u8]) -> Result<usize, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let name = std::ffi::CString::new(name).map_err(|_| Error::EINVAL)?;
+    unsafe { lgetxattr_unsafe(path.as_ptr() as *const u8, name.as_ptr() as *const u8, value) }
+}
+/// Get the value of an extended attribute of an open file.
+/// 
+/// If `value` is empty the size of the value is returned without reading it.
+/// Returns `ERANGE` if `value` is too small.
+/// 
+/// # Safety
+/// `name` must be null-terminated.
+#[inline]
+pub unsafe fn fgetxattr_unsafe<'a, F: AsRef<Fd<'a>>>(fd: F, name: *const u8, value: &mut [u8]) -> Result<usize, Error> {
+    let maybe: isize;
+    syscall!{
+        193(fd.as_ref().raw(), name, value.as_mut_ptr(), value.len()) -> maybe
+    }
+    Error::maybe_usize(maybe)
+}
+/// Get the value of an extended attribute of an open file.
+/// 
+/// If `value` is empty the size of the value is returned without reading it.
+/// Returns `ERANGE` if `value` is too small.
+#[inline]
+#[cfg(feature = "std")]
+pub fn fgetxattr<'a, F: AsRef<Fd<'a>>>(fd: F, name: &str, value: &mut [u8]) -> Result<usize, Error> {
+    let name = std::ffi::CString::new(name).map_err(|_| Error::EINVAL)?;
+    unsafe { fgetxattr_unsafe(fd, name.as_ptr() as *const u8, value) }
+}
+/// List the names of the extended attributes of a file.
+/// 
+/// The names are written null-separated and may be iterated with `XattrNames`.
+/// If `list` is empty the size of the list is returned without reading it.
+/// Returns `ERANGE` if `list` is too small.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn listxattr_unsafe(path: *const u8, list: &mut [u8]) -> Result<usize, Error> {
+    let maybe: isize;
+    syscall!{
+        194(path, list.as_mut_ptr(), list.len()) -> maybe
+    }
+    Error::maybe_usize(maybe)
+}
+/// List the names of the extended attributes of a file.
+/// 
+/// The names are written null-separated and may be iterated with `XattrNames`.
+/// If `list` is empty the size of the list is returned without reading it.
+/// Returns `ERANGE` if `list` is too small.
+#[inline]
+#[cfg(feature = "std")]
+pub fn listxattr<P: AsRef<std::path::Path>>(path: P, list: &mut [u8]) -> Result<usize, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { listxattr_unsafe(path.as_ptr() as *const u8, list) }
+}
+/// List the names of the extended attributes of a file without following symlinks.
+/// 
+/// The names are written null-separated and may be iterated with `XattrNames`.
+/// If `list` is empty the size of the list is returned without reading it.
+/// Returns `ERANGE` if `list` is too small.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn llistxattr_unsafe(path: *const u8, list: &mut [u8]) -> Result<usize, Error> {
+    let maybe: isize;
+    syscall!{
+        195(path, list.as_mut_ptr(), list.len()) -> maybe
+    }
+    Error::maybe_usize(maybe)
+}
+/// List the names of the extended attributes of a file without following symlinks.
+/// 
+/// The names are written null-separated and may be iterated with `XattrNames`.
+/// If `list` is empty the size of the list is returned without reading it.
+/// Returns `ERANGE` if `list` is too small.
+#[inline]
+#[cfg(feature = "std")]
+pub fn llistxattr<P: AsRef<std::path::Path>>(path: P, list: &mut [u8]) -> Result<usize, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { llistxattr_unsafe(path.as_ptr() as *const u8, list) }
+}
+/// List the names of the extended attributes of an open file.
+/// 
+/// The names are written null-separated and may be iterated with `XattrNames`.
+/// If `list` is empty the size of the list is returned without reading it.
+/// Returns `ERANGE` if `list` is too small.
+#[inline]
+pub fn flistxattr<'a, F: AsRef<Fd<'a>>>(fd: F, list: &mut [u8]) -> Result<usize, Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            196(fd.as_ref().raw(), list.as_mut_ptr(), list.len()) -> maybe
+        }
+    }
+    Error::maybe_usize(maybe)
+}
+/// Remove an extended attribute of a file.
+/// 
+/// # Safety
+/// `path` and `name` must be null-terminated.
+#[inline]
+pub unsafe fn removexattr_unsafe(path: *const u8, name: *const u8) -> Result<(), Error> {
+    let maybe: isize;
+    syscall!{
+        197(path, name) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Remove an extended attribute of a file.
+#[inline]
+#[cfg(feature = "std")]
+pub fn removexattr<P: AsRef<std::path::Path>>(path: P, name: &str) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let name = std::ffi::CString::new(name).map_err(|_| Error::EINVAL)?;
+    unsafe { removexattr_unsafe(path.as_ptr() as *const u8, name.as_ptr() as *const u8) }
+}
+/// Remove an extended attribute of a file without following symlinks.
+/// 
+/// # Safety
+/// `path` and `name` must be null-terminated.
+#[inline]
+pub unsafe fn lremovexattr_unsafe(path: *const u8, name: *const u8) -> Result<(), Error> {
+    let maybe: isize;
+    syscall!{
+        198(path, name) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Remove an extended attribute of a file without following symlinks.
+#[inline]
+#[cfg(feature = "std")]
+pub fn lremovexattr<P: AsRef<std::path::Path>>(path: P, name: &str) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let name = std::ffi::CString::new(name).map_err(|_| Error::EINVAL)?;
+    unsafe { lremovexattr_unsafe(path.as_ptr() as *const u8, name.as_ptr() as *const u8) }
+}
+/// Remove an extended attribute of an open file.
+/// 
+/// # Safety
+/// `name` must be null-terminated.
+#[inline]
+pub unsafe fn fremovexattr_unsafe<'a, F: AsRef<Fd<'a>>>(fd: F, name: *const u8) -> Result<(), Error> {
+    let maybe: isize;
+    syscall!{
+        199(fd.as_ref().raw(), name) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Remove an extended attribute of an open file.
+#[inline]
+#[cfg(feature = "std")]
+pub fn fremovexattr<'a, F: AsRef<Fd<'a>>>(fd: F, name: &str) -> Result<(), Error> {
+    let name = std::ffi::CString::new(name).map_err(|_| Error::EINVAL)?;
+    unsafe { fremovexattr_unsafe(fd, name.as_ptr() as *const u8) }
+}
+
+/// Wait while `word` holds `expected`, until woken by `futex_wake` or until `deadline` passes.
+/// 
+/// The deadline is an absolute time on the `MONOTONIC` clock, or on the `REALTIME` clock with
+/// `FutexFlags::CLOCK_REALTIME`. `word` may be in memory shared with other processes unless `FutexFlags::PRIVATE`
+/// is given. Returns `EAGAIN` if `word` did not hold `expected`, `ETIMEDOUT` once the deadline passes and `EINTR` if
+/// interrupted by a signal. Spurious wake ups are possible, so the caller should check its condition again.
+#[inline]
+pub fn futex_wait(word: &core::sync::atomic::AtomicU32, expected: u32, deadline: Option<&Timespec>, flags: FutexFlags) -> Result<(), Error> {
+    const FUTEX_WAIT_BITSET: u32 = 9;
+    const FUTEX_BITSET_MATCH_ANY: u32 = u32::MAX;
+    let maybe: isize;
+    let op = FUTEX_WAIT_BITSET | u32::from(flags);
+    let deadline = deadline.map_or(core::ptr::null(), |deadline| deadline as *const Timespec);
+    unsafe {
+        syscall!{
+            202(word.as_ptr(), op, expected, deadline, core::ptr::null::<u32>(), FUTEX_BITSET_MATCH_ANY) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Wake up to `count` waiters on `word`, returning the number woken.
+/// 
+/// `FutexFlags::PRIVATE` must match the flags the waiters used.
+#[inline]
+pub fn futex_wake(word: &core::sync::atomic::AtomicU32, count: u32, flags: FutexFlags) -> Result<usize, Error> {
+    const FUTEX_WAKE: u32 = 1;
+    let count = count.min(i32::MAX as u32);
+    let woken: isize;
+    let op = FUTEX_WAKE | u32::from(flags & FutexFlags::PRIVATE);
+    unsafe {
+        syscall!{
+            202(word.as_ptr(), op, count) -> woken
+        }
+    }
+    Error::maybe_usize(woken)
+}
+/// Read directory entries from an open directory.
+/// 
+/// Returns the filled portion of the buffer, which may be parsed with `dirent::Entries`.
+/// An empty slice is returned at the end of the directory.
+#[inline]
+pub fn getdents64<'a, 'b, F: AsRef<Fd<'b>>>(fd: F, buffer: &'a mut [u8]) -> Result<&'a [u8], Error> {
+    let count;
+    unsafe {
+        syscall!{
+            217(fd.as_ref().raw(), buffer.as_mut_ptr(), buffer.len()) -> count
+        }
+    }
+    Error::maybe_usize(count).map(|len| &buffer[..len])
+}
+
+/// Read the time of a clock.
+#[inline]
+pub fn clock_gettime(clock: Clock) -> Result<Timespec, Error> {
+    let maybe: isize;
+    let mut time = Timespec::new(0, 0);
+    let clock: u32 = clock.into();
+    unsafe {
+        syscall!{
+            228(clock, &mut time as *mut Timespec) -> maybe
+        }
+    }
+    Error::maybe(maybe).map(|_| time)
+}
+/// Wait for an entry to enter the epoll ready list.
+#[inline]
+pub fn epoll_wait<'a, 'b, E: AsRef<Fd<'b>>>(epoll: E, events: &'a mut [std::mem::MaybeUninit<epoll::Event>], timeout: u32) -> Result<&'a [epoll::Event], Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            232(epoll.as_ref().raw(), events.as_mut_ptr(), events.len(), timeout) -> maybe
+        }
+    }
+    Error::maybe_u32(maybe).map(|count| unsafe { std::mem::transmute(&events[..count as usize]) })
+}
+/// Modify an entry in the epoll wait list.
+#[inline]
+pub fn epoll_ctl<'a, 'b, E: AsRef<Fd<'a>>, F: AsRef<Fd<'b>>>(epoll: E, fd: F, cmd: epoll::Cntl) -> Result<(), Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            233(epoll.as_ref().raw(), cmd.cmd(), fd.as_ref().raw(), cmd.arg().map(|p| p as *const epoll::Event).unwrap_or(core::ptr::null())) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Set the NUMA memory policy for a range of memory.
+/// 
+/// With `MoveFlags::MOVE` pages already allocated are moved to conform to the policy, or with `MOVE_ALL` even pages
+/// shared with other processes, which requires `CAP_SYS_NICE`. With `STRICT`, `EIO` is returned if any page does not
+/// conform afterwards.
+#[inline]
+pub fn mbind(address: *mut core::ffi::c_void, length: usize, policy: mempolicy::MemPolicy, flags: mempolicy::ModeFlags, nodes: Option<&mempolicy::NodeMask>, move_flags: mempolicy::MoveFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let mode = u32::from(policy) | u32::from(flags);
+    let nodes = nodes.map_or(core::ptr::null(), |nodes| nodes as *const mempolicy::NodeMask);
+    let move_flags: u32 = move_flags.into();
+    unsafe {
+        syscall!{
+            237(address, length, mode, nodes, mempolicy::NodeMask::max_node(), move_flags) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Set the NUMA memory policy of the calling thread, which applies to its future allocations.
+/// 
+/// `MemPolicy::DEFAULT` and `LOCAL` take no nodes.
+#[inline]
+pub fn set_mempolicy(policy: mempolicy::MemPolicy, flags: mempolicy::ModeFlags, nodes: Option<&mempolicy::NodeMask>) -> Result<(), Error> {
+    let maybe: isize;
+    let mode = u32::from(policy) | u32::from(flags);
+    let nodes = nodes.map_or(core::ptr::null(), |nodes| nodes as *const mempolicy::NodeMask);
+    unsafe {
+        syscall!{
+            238(mode, nodes, mempolicy::NodeMask::max_node()) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Get the NUMA memory policy of the calling thread, or with `GetFlags::ADDRESS` of the memory at `address`.
+/// 
+/// Returns the mode, which `mempolicy::split_mode` splits in to its policy and flags, and fills `nodes`.
+/// With `GetFlags::NODE` the node the memory at `address` is allocated on is returned instead, or with
+/// `MEMS_ALLOWED` alone the nodes the thread may allocate on are filled.
+#[inline]
+pub fn get_mempolicy(nodes: Option<&mut mempolicy::NodeMask>, address: *const core::ffi::c_void, flags: mempolicy::GetFlags) -> Result<u32, Error> {
+    let maybe: isize;
+    let mut mode = 0u32;
+    let (nodes, max_node) = match nodes {
+        Some(nodes) => (nodes as *mut mempolicy::NodeMask, mempolicy::NodeMask::max_node()),
+        None => (core::ptr::null_mut(), 0)
+    };
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            239(&mut mode as *mut u32, nodes, max_node, address, flags) -> maybe
+        }
+    }
+    Error::maybe(maybe).map(|_| mode)
+}
+/// Add a watch for changes to a file or directory to an inotify instance.
+/// 
+/// If the path is already watched the existing watch is modified and returned.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn inotify_add_watch_unsafe<'a, F: AsRef<Fd<'a>>>(inotify: F, path: *const u8, mask: inotify::WatchMask) -> Result<inotify::Watch, Error> {
+    let maybe: isize;
+    let mask: u32 = mask.into();
+    syscall!{
+        254(inotify.as_ref().raw(), path, mask) -> maybe
+    }
+    Error::maybe_u32(maybe).map(|wd| inotify::Watch(wd as i32))
+}
+/// Add a watch for changes to a file or directory to an inotify instance.
+/// 
+/// If the path is already watched the existing watch is modified and returned.
+#[inline]
+#[cfg(feature = "std")]
+pub fn inotify_add_watch<'a, F: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(inotify: F, path: P, mask: inotify::WatchMask) -> Result<inotify::Watch, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { inotify_add_watch_unsafe(inotify, path.as_ptr() as *const u8, mask) }
+}
+/// Remove a watch from an inotify instance.
+#[inline]
+pub fn inotify_rm_watch<'a, F: AsRef<Fd<'a>>>(inotify: F, inotify::Watch(wd): inotify::Watch) -> Result<(), Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            255(inotify.as_ref().raw(), wd) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+
+/// Open a file relative to a directory.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn openat_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, open::Flags(flags): open::Flags, open::Mode(mode): open::Mode) -> Result<File, Error> {
+    let fd: isize;
+    syscall!{
+        257(dir.as_ref().raw(), path, flags, mode) -> fd
+    }
+    fd.try_into()
+}
+/// Open a file relative to a directory.
+#[inline]
+#[cfg(feature = "std")]
+pub fn openat<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, flags: open::Flags, mode: open::Mode) -> Result<File, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { openat_unsafe(dir, path.as_ptr() as *const u8, flags, mode) }
+}
+/// Create a directory relative to a directory.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn mkdirat_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, open::Mode(mode): open::Mode) -> Result<(), Error> {
+    let maybe: isize;
+    syscall!{
+        258(dir.as_ref().raw(), path, mode) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Create a directory relative to a directory.
+#[inline]
+#[cfg(feature = "std")]
+pub fn mkdirat<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, mode: open::Mode) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { mkdirat_unsafe(dir, path.as_ptr() as *const u8, mode) }
+}
+/// Change the owner and group of a file relative to a directory.
+/// 
+/// `None` leaves the respective ID unchanged.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn fchownat_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, uid: Option<u32>, gid: Option<u32>, flags: AtFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        260(dir.as_ref().raw(), path, uid.unwrap_or(u32::MAX), gid.unwrap_or(u32::MAX), flags) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Change the owner and group of a file relative to a directory.
+/// 
+/// `None` leaves the respective ID unchanged.
+#[inline]
+#[cfg(feature = "std")]
+pub fn fchownat<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, uid: Option<u32>, gid: Option<u32>, flags: AtFlags) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { fchownat_unsafe(dir, path.as_ptr() as *const u8, uid, gid, flags) }
+}
+/// Get information about a file relative to a directory.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn fstatat_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, flags: AtFlags) -> Result<Stat, Error> {
+    let mut stat = core::mem::MaybeUninit::uninit();
+    let err;
+    let flags: u32 = flags.into();
+    syscall!{
+        262(dir.as_ref().raw(), path, stat.as_mut_ptr(), flags) -> err
+    }
+    Error::maybe(err).map(|_| stat.assume_init())
+}
+/// Get information about a file relative to a directory.
+#[inline]
+#[cfg(feature = "std")]
+pub fn fstatat<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, flags: AtFlags) -> Result<Stat, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { fstatat_unsafe(dir, path.as_ptr() as *const u8, flags) }
+}
+/// Remove an entry from the file system relative to a directory.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn unlinkat_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, flags: UnlinkFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        263(dir.as_ref().raw(), path, flags) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Remove an entry from the file system relative to a directory.
+#[inline]
+#[cfg(feature = "std")]
+pub fn unlinkat<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, flags: UnlinkFlags) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { unlinkat_unsafe(dir, path.as_ptr() as *const u8, flags) }
+}
+/// Rename a file, replacing the destination if it exists.
+/// 
+/// # Safety
+/// `old_path` and `new_path` must be null-terminated.
+#[inline]
+pub unsafe fn renameat_unsafe<'a, 'b, D: AsRef<Fd<'a>>, E: AsRef<Fd<'b>>>(old_dir: D, old_path: *const u8, new_dir: E, new_path: *const u8) -> Result<(), Error> {
+    let maybe: isize;
+    syscall!{
+        264(old_dir.as_ref().raw(), old_path, new_dir.as_ref().raw(), new_path) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Rename a file, replacing the destination if it exists.
+#[inline]
+#[cfg(feature = "std")]
+pub fn renameat<'a, 'b, D: AsRef<Fd<'a>>, E: AsRef<Fd<'b>>, P: AsRef<std::path::Path>, Q: AsRef<std::path::Path>>(old_dir: D, old_path: P, new_dir: E, new_path: Q) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let old_path = std::ffi::CString::new(old_path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let new_path = std::ffi::CString::new(new_path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { renameat_unsafe(old_dir, old_path.as_ptr() as *const u8, new_dir, new_path.as_ptr() as *const u8) }
+}
+/// Create a new hard link to an existing file.
+/// 
+/// # Safety
+/// `old_path` and `new_path` must be null-terminated.
+#[inline]
+pub unsafe fn linkat_unsafe<'a, 'b, D: AsRef<Fd<'a>>, E: AsRef<Fd<'b>>>(old_dir: D, old_path: *const u8, new_dir: E, new_path: *const u8, flags: AtFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        265(old_dir.as_ref().raw(), old_path, new_dir.as_ref().raw(), new_path, flags) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Create a new hard link to an existing file.
+#[inline]
+#[cfg(feature = "std")]
+pub fn linkat<'a, 'b, D: AsRef<Fd<'a>>, E: AsRef<Fd<'b>>, P: AsRef<std::path::Path>, Q: AsRef<std::path::Path>>(old_dir: D, old_path: P, new_dir: E, new_path: Q, flags: AtFlags) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let old_path = std::ffi::CString::new(old_path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let new_path = std::ffi::CString::new(new_path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { linkat_unsafe(old_dir, old_path.as_ptr() as *const u8, new_dir, new_path.as_ptr() as *const u8, flags) }
+}
+/// Create a symbolic link relative to a directory.
+/// 
+/// # Safety
+/// `target` and `path` must be null-terminated.
+#[inline]
+pub unsafe fn symlinkat_unsafe<'a, D: AsRef<Fd<'a>>>(target: *const u8, dir: D, path: *const u8) -> Result<(), Error> {
+    let maybe: isize;
+    syscall!{
+        266(target, dir.as_ref().raw(), path) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Create a symbolic link relative to a directory.
+#[inline]
+#[cfg(feature = "std")]
+pub fn symlinkat<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>, Q: AsRef<std::path::Path>>(target: P, dir: D, path: Q) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let target = std::ffi::CString::new(target.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { symlinkat_unsafe(target.as_ptr() as *const u8, dir, path.as_ptr() as *const u8) }
+}
+/// Read the target of a symbolic link relative to a directory.
+/// 
+/// Returns the filled portion of the buffer. The target is truncated if the buffer is too small.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn readlinkat_unsafe<'a, 'b, D: AsRef<Fd<'b>>>(dir: D, path: *const u8, buffer: &'a mut [u8]) -> Result<&'a [u8], Error> {
+    let count;
+    syscall!{
+        267(dir.as_ref().raw(), path, buffer.as_mut_ptr(), buffer.len()) -> count
+    }
+    Error::maybe_usize(count).map(|len| &buffer[..len])
+}
+/// Read the target of a symbolic link relative to a directory.
+/// 
+/// Returns the filled portion of the buffer. The target is truncated if the buffer is too small.
+#[inline]
+#[cfg(feature = "std")]
+pub fn readlinkat<'a, 'b, D: AsRef<Fd<'b>>, P: AsRef<std::path::Path>>(dir: D, path: P, buffer: &'a mut [u8]) -> Result<&'a [u8], Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { readlinkat_unsafe(dir, path.as_ptr() as *const u8, buffer) }
+}
+/// Change the access mode of a file relative to a directory.
+/// 
+/// Symlinks are always followed.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn fchmodat_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, open::Mode(mode): open::Mode) -> Result<(), Error> {
+    let maybe: isize;
+    syscall!{
+        268(dir.as_ref().raw(), path, mode) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Change the access mode of a file relative to a directory.
+/// 
+/// Symlinks are always followed.
+#[inline]
+#[cfg(feature = "std")]
+pub fn fchmodat<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, mode: open::Mode) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { fchmodat_unsafe(dir, path.as_ptr() as *const u8, mode) }
+}
+/// Check whether the process can access a file relative to a directory, using its real user and group IDs.
+/// 
+/// Symlinks are always followed. See `faccessat2` for a variant that takes flags.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn faccessat_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, mode: AccessMode) -> Result<(), Error> {
+    let maybe: isize;
+    let mode: u32 = mode.into();
+    syscall!{
+        269(dir.as_ref().raw(), path, mode) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Check whether the process can access a file relative to a directory, using its real user and group IDs.
+/// 
+/// Symlinks are always followed. See `faccessat2` for a variant that takes flags.
+#[inline]
+#[cfg(feature = "std")]
+pub fn faccessat<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, mode: AccessMode) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { faccessat_unsafe(dir, path.as_ptr() as *const u8, mode) }
+}
+/// Move the calling thread in to new namespaces, or stop sharing resources with other processes.
+/// 
+/// `NEW_USER` requires that the process is single-threaded.
+#[inline]
+pub fn unshare(flags: clone::Flags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u64 = flags.into();
+    unsafe {
+        syscall!{
+            272(flags) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Flush a byte range of an open file to the storage device.
+/// 
+/// No metadata is flushed, so this provides no durability guarantees on its own.
+/// A `length` of zero extends to the end of the file.
+#[inline]
+pub fn sync_file_range<'a, F: AsRef<Fd<'a>>>(fd: F, offset: u64, length: u64, flags: SyncFileRangeFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            277(fd.as_ref().raw(), offset, length, flags) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Move pages of a process to other NUMA nodes, or find which nodes they are on when `nodes` is `None`.
+/// 
+/// `pages` are addresses within the pages, and `nodes` gives the destination of each. Each entry of `status` is set
+/// to the node of the page, or a negative error number such as `-ENOENT` for a page that is not present.
+/// `MoveFlags::MOVE_ALL` also moves pages shared with other processes, which requires `CAP_SYS_NICE`.
+/// Returns the number of pages that could not be moved. The slices must have the same length, otherwise `EINVAL` is
+/// returned.
+#[inline]
+pub fn move_pages(pid: u32, pages: &[*const core::ffi::c_void], nodes: Option<&[i32]>, status: &mut [i32], flags: mempolicy::MoveFlags) -> Result<usize, Error> {
+    if status.len() != pages.len() || nodes.is_some_and(|nodes| nodes.len() != pages.len()) {
+        return Err(Error::EINVAL)
+    }
+    let count: isize;
+    let nodes = nodes.map_or(core::ptr::null(), |nodes| nodes.as_ptr());
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            279(pid, pages.len(), pages.as_ptr(), nodes, status.as_mut_ptr(), flags) -> count
+        }
+    }
+    Error::maybe_usize(count)
+}
+/// Set the access and modification timestamps of a file relative to a directory.
+/// 
+/// `times` holds the access then modification time. If `None`, both are set to the current time.
+/// 
+/// # Safety
+/// `path` must be null-terminated, or null to operate on `dir` itself.
+#[inline]
+pub unsafe fn utimensat_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, times: Option<&[Timespec; 2]>, flags: AtFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    let times = times.map(|t| t as *const _).unwrap_or(core::ptr::null());
+    syscall!{
+        280(dir.as_ref().raw(), path, times, flags) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Set the access and modification timestamps of a file relative to a directory.
+/// 
+/// `times` holds the access then modification time. If `None`, both are set to the current time.
+#[inline]
+#[cfg(feature = "std")]
+pub fn utimensat<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, times: Option<&[Timespec; 2]>, flags: AtFlags) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { utimensat_unsafe(dir, path.as_ptr() as *const u8, times, flags) }
+}
+/// Set the access and modification timestamps of an open file.
+/// 
+/// `times` holds the access then modification time. If `None`, both are set to the current time.
+#[inline]
+pub fn futimens<'a, F: AsRef<Fd<'a>>>(fd: F, times: Option<&[Timespec; 2]>) -> Result<(), Error> {
+    // Safety: a null path operates on the file descriptor itself
+    unsafe { utimensat_unsafe(fd, core::ptr::null(), times, AtFlags::NONE) }
+}
+/// Manipulate the allocated disk space of an open file.
+#[inline]
+pub fn fallocate<'a, F: AsRef<Fd<'a>>>(fd: F, mode: FallocateMode, offset: u64, length: u64) -> Result<(), Error> {
+    let maybe: isize;
+    let mode: u32 = mode.into();
+    unsafe {
+        syscall!{
+            285(fd.as_ref().raw(), mode, offset, length) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+
+/// Open an epoll file descriptor.
+#[inline]
+pub fn epoll_create(flags: epoll::Flags) -> Result<File, Error> {
+    let maybe: isize;
+    let flags : u32 = flags.into();
+    unsafe {
+        syscall!{
+            291(flags) -> maybe
+        }
+    }
+    maybe.try_into()
+}
+
+/// Open an inotify file descriptor.
+/// 
+/// Events are read from the file descriptor and may be parsed with `inotify::Events`.
+/// The file descriptor may be waited on with epoll.
+#[inline]
+pub fn inotify_init(flags: inotify::Flags) -> Result<File, Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            294(flags) -> maybe
+        }
+    }
+    maybe.try_into()
+}
+
+/// Flush all modified data and metadata of the file system containing an open file.
+#[inline]
+pub fn syncfs<'a, F: AsRef<Fd<'a>>>(fd: F) -> Result<(), Error> {
+    let maybe: isize;
+    unsafe {
+        syscall!{
+            306(fd.as_ref().raw()) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+
+/// Read the memory of another process in to local buffers.
+/// 
+/// The local buffers are filled in order from the remote regions in order. Returns the number of bytes read, which is
+/// short if a remote region is only partly accessible; the transfer stops at the first page that cannot be read.
+/// `EFAULT` is returned if nothing could be read. Requires the same permission as `ptrace` attaching to `pid`.
+#[inline]
+pub fn process_vm_readv(pid: u32, local: &[IoVecMut], remote: &[RemoteIoVec]) -> Result<usize, Error> {
+    // Safety: IoVecMut can only be constructed with potentially invalid values through an unsafe function.
+    let count: isize;
+    unsafe {
+        syscall!{
+            310(pid, local.as_ptr(), local.len(), remote.as_ptr(), remote.len(), 0) -> count
+        }
+    }
+    Error::maybe_usize(count)
+}
+/// Write local buffers in to the memory of another process.
+/// 
+/// Returns the number of bytes written, which is short if a remote region is only partly writable.
+/// 
+/// # Safety
+/// If `pid` is the calling process, the remote regions must be valid for writes and not in use, as when writing
+/// through a raw pointer.
+#[inline]
+pub unsafe fn process_vm_writev(pid: u32, local: &[IoVec], remote: &[RemoteIoVec]) -> Result<usize, Error> {
+    let count: isize;
+    syscall!{
+        311(pid, local.as_ptr(), local.len(), remote.as_ptr(), remote.len(), 0) -> count
+    }
+    Error::maybe_usize(count)
+}
+
+/// Rename a file, with control over how an existing destination is treated.
+/// 
+/// - `NO_REPLACE` fails with `EEXIST` rather than replacing an existing destination.
+/// - `EXCHANGE` atomically swaps the source and destination, which must both exist.
+/// - `WHITEOUT` leaves a whiteout object in place of the source, for overlay file systems.
+/// 
+/// `EXCHANGE` cannot be combined with the other flags, and `EINVAL` is returned if it is. Rather than falling back to
+/// a plain rename, `EOPNOTSUPP` is returned if the file system does not support the requested flags, and `ENOSYS` if
+/// the kernel does not support `renameat2` at all. Renaming a directory beneath itself, or with `EXCHANGE` swapping a
+/// directory with one beneath it, still fails with `EINVAL`.
+/// 
+/// The kernel reports both cases as `EINVAL`, so they are told apart by looking at the paths after the rename has
+/// failed. If the tree is changed concurrently, the wrong one of the two errors may be returned.
+/// 
+/// # Safety
+/// `old_path` and `new_path` must be null-terminated.
+#[inline]
+pub unsafe fn renameat2_unsafe<'a, 'b, D: AsRef<Fd<'a>>, E: AsRef<Fd<'b>>>(old_dir: D, old_path: *const u8, new_dir: E, new_path: *const u8, flags: RenameFlags) -> Result<(), Error> {
+    // The kernel's own checks of the flags also fail with `EINVAL`, so are made here to tell them apart
+    if !RenameFlags::MASK.all(flags) || flags.any(RenameFlags::EXCHANGE) && flags.any(RenameFlags::NO_REPLACE | RenameFlags::WHITEOUT) {
+        return Err(Error::EINVAL)
+    }
+    let (old_dir, new_dir) = (old_dir.as_ref(), new_dir.as_ref());
+    let maybe: isize;
+    let raw: u32 = flags.into();
+    syscall!{
+        316(old_dir.raw(), old_path, new_dir.raw(), new_path, raw) -> maybe
+    }
+    match Error::maybe(maybe) {
+        // Otherwise the file system refused the flags
+        Err(Error::EINVAL) if flags != RenameFlags::NONE
+            && !beneath(new_dir, new_path, old_dir, old_path)
+            && !(flags.any(RenameFlags::EXCHANGE) && beneath(old_dir, old_path, new_dir, new_path)) => Err(Error::EOPNOTSUPP),
+        result => result
+    }
+}
+/// Whether the parent of `path` is `ancestor` or beneath it, so that the kernel refuses to rename one in to the place
+/// of the other with `EINVAL` before the file system sees the flags.
+/// 
+/// # Safety
+/// `path` and `ancestor` must be null-terminated.
+unsafe fn beneath(dir: &Fd, path: *const u8, ancestor_dir: &Fd, ancestor: *const u8) -> bool {
+    let Ok(ancestor) = fstatat_unsafe(ancestor_dir, ancestor, AtFlags::SYMLINK_NO_FOLLOW) else {
+        return false
+    };
+    if ancestor.file_type() != FileType::DIRECTORY {
+        return false
+    }
+    let path = core::ffi::CStr::from_ptr(path as *const _).to_bytes();
+    let parent = match path.iter().rposition(|&b| b == b'/') {
+        Some(0) => b"/",
+        Some(end) => &path[..end],
+        None => &b"."[..]
+    };
+    let mut buffer = [0; crate::fs::PATH_MAX];
+    let Some(path) = buffer.get_mut(..parent.len() + 1) else {
+        return false
+    };
+    path[..parent.len()].copy_from_slice(parent);
+    let flags = open::Flags::PATH | open::Flags::DIRECTORY | open::Flags::CLOSE_ON_EXEC;
+    let Ok(mut dir) = openat_unsafe(dir, path.as_ptr(), flags, open::Mode::NONE) else {
+        return false
+    };
+    let Ok(mut stat) = fstat(&dir) else {
+        return false
+    };
+    loop {
+        if (stat.device_id, stat.inode) == (ancestor.device_id, ancestor.inode) {
+            return true
+        }
+        let Ok(parent) = openat_unsafe(&dir, c"..".as_ptr() as *const u8, flags, open::Mode::NONE) else {
+            return false
+        };
+        let Ok(parent_stat) = fstat(&parent) else {
+            return false
+        };
+        // The root is its own parent
+        if (parent_stat.device_id, parent_stat.inode) == (stat.device_id, stat.inode) {
+            return false
+        }
+        (dir, stat) = (parent, parent_stat);
+    }
+}
+/// Rename a file, with control over how an existing destination is treated.
+/// 
+/// See `renameat2_unsafe` for the meaning of the flags and how unsupported flags are reported.
+#[inline]
+#[cfg(feature = "std")]
+pub fn renameat2<'a, 'b, D: AsRef<Fd<'a>>, E: AsRef<Fd<'b>>, P: AsRef<std::path::Path>, Q: AsRef<std::path::Path>>(old_dir: D, old_path: P, new_dir: E, new_path: Q, flags: RenameFlags) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let old_path = std::ffi::CString::new(old_path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let new_path = std::ffi::CString::new(new_path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { renameat2_unsafe(old_dir, old_path.as_ptr() as *const u8, new_dir, new_path.as_ptr() as *const u8, flags) }
+}
+
+/// Create an anonymous file.
+/// 
+/// The size of huge pages is selected with one of the `HUGE_*` flags alongside `HUGETLB`.
+/// With `ALLOW_SEALING`, seals may be added with `File::add_seals`. `NO_EXEC_SEAL` creates the file without execute
+/// permission and with `Seals::EXEC` already applied.
+/// 
+/// # Safety
+/// `name` must be null-terminated.
+#[inline]
+pub unsafe fn memfd_create_unsafe(name: *const u8, flags: MemfdFlags) -> Result<File, Error> {
+    let fd: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        319(name, flags) -> fd
+    }
+    fd.try_into()
+}
+/// Create an anonymous file.
+/// 
+/// The size of huge pages is selected with one of the `HUGE_*` flags alongside `HUGETLB`.
+/// With `ALLOW_SEALING`, seals may be added with `File::add_seals`. `NO_EXEC_SEAL` creates the file without execute
+/// permission and with `Seals::EXEC` already applied.
+#[inline]
+#[cfg(feature = "std")]
+pub fn memfd_create(name: &str, flags: MemfdFlags) -> Result<File, Error> {
+    let name = std::ffi::CString::new(name.as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { memfd_create_unsafe(name.as_ptr() as *const u8, flags) }
+}
+/// Replace the program of the calling process with a file relative to a directory.
+/// 
+/// Only returns on failure, with the error. Accepts `AtFlags::EMPTY_PATH`, to execute `dir` itself, and
+/// `AtFlags::SYMLINK_NO_FOLLOW`, to fail with `ELOOP` if the file is a symlink.
+/// 
+/// # Safety
+/// `path` must be null-terminated, and `argv` and `envp` must be null-terminated arrays of null-terminated strings.
+#[inline]
+pub unsafe fn execveat_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, argv: *const *const u8, envp: *const *const u8, flags: AtFlags) -> Error {
+    let ret: isize;
+    let dir = dir.as_ref().raw();
+    let flags: u32 = flags.into();
+    syscall!{
+        322(dir, path, argv, envp, flags) -> ret
+    }
+    Error(-ret as u32)
+}
+/// Replace the program of the calling process with a file relative to a directory.
+/// 
+/// Only returns on failure, with the error. Accepts `AtFlags::EMPTY_PATH`, to execute `dir` itself, and
+/// `AtFlags::SYMLINK_NO_FOLLOW`, to fail with `ELOOP` if the file is a symlink.
+#[inline]
+pub fn execveat<'a, D: AsRef<Fd<'a>>>(dir: D, path: &core::ffi::CStr, argv: &Argv, envp: &Argv, flags: AtFlags) -> Error {
+    unsafe { execveat_unsafe(dir, path.as_ptr() as *const u8, argv.as_ptr(), envp.as_ptr(), flags) }
+}
+/// Replace the program of the calling process with an open file, such as a `memfd_create` file.
+/// 
+/// Only returns on failure, with the error. A script run this way must not be opened with `CLOSE_ON_EXEC`, or the
+/// interpreter cannot open it and fails with `ENOENT`.
+#[inline]
+pub fn fexecve<'a, F: AsRef<Fd<'a>>>(fd: F, argv: &Argv, envp: &Argv) -> Error {
+    execveat(fd, c"", argv, envp, AtFlags::EMPTY_PATH)
+}
+/// Create a userfaultfd, through which page faults in registered ranges of memory are handled by user space.
+/// 
+/// Unprivileged processes may need `userfaultfd::Flags::USER_MODE_ONLY`, depending on `vm.unprivileged_userfaultfd`.
+/// The API must be negotiated with `Userfaultfd::api` before use.
+#[inline]
+pub fn userfaultfd(flags: userfaultfd::Flags) -> Result<userfaultfd::Userfaultfd, Error> {
+    let fd: isize;
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            323(flags) -> fd
+        }
+    }
+    fd.try_into().map(userfaultfd::Userfaultfd)
+}
+/// Issue a memory barrier on other threads, or register for a kind of barrier.
+/// 
+/// Registering before issuing an expedited barrier is required. `QUERY` and `GET_REGISTRATIONS` return a mask,
+/// tested with `MembarrierCmd::is_in`. `cpu` restricts `PRIVATE_EXPEDITED_RSEQ` to a single CPU, and must be `None`
+/// for the other commands.
+#[inline]
+pub fn membarrier(cmd: MembarrierCmd, cpu: Option<u32>) -> Result<u32, Error> {
+    const MEMBARRIER_CMD_FLAG_CPU: u32 = 1;
+    let maybe: isize;
+    let (flags, cpu) = match cpu {
+        Some(cpu) => (MEMBARRIER_CMD_FLAG_CPU, cpu),
+        None => (0, 0)
+    };
+    let cmd: u32 = cmd.into();
+    unsafe {
+        syscall!{
+            324(cmd, flags, cpu) -> maybe
+        }
+    }
+    Error::maybe_u32(maybe)
+}
+/// Lock a region of memory in to RAM.
+/// 
+/// With `LockFlags::ON_FAULT` pages are locked as they are faulted in, rather than all at once.
+#[inline]
+pub fn mlock2(address: *const core::ffi::c_void, length: usize, flags: mmap::LockFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            325(address, length, flags) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Open or clone a mount as a file descriptor.
+/// 
+/// With `OpenTreeFlags::CLONE` a detached copy of the mount is created, which may be attached elsewhere with `move_mount`.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn open_tree_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, flags: mount::OpenTreeFlags) -> Result<File, Error> {
+    let fd: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        428(dir.as_ref().raw(), path, flags) -> fd
+    }
+    fd.try_into()
+}
+/// Open or clone a mount as a file descriptor.
+/// 
+/// With `OpenTreeFlags::CLONE` a detached copy of the mount is created, which may be attached elsewhere with `move_mount`.
+#[inline]
+#[cfg(feature = "std")]
+pub fn open_tree<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, flags: mount::OpenTreeFlags) -> Result<File, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { open_tree_unsafe(dir, path.as_ptr() as *const u8, flags) }
+}
+/// Move a mount, or attach a detached mount, to a new location.
+/// 
+/// # Safety
+/// `from_path` and `to_path` must be null-terminated.
+#[inline]
+pub unsafe fn move_mount_unsafe<'a, 'b, D: AsRef<Fd<'a>>, E: AsRef<Fd<'b>>>(from_dir: D, from_path: *const u8, to_dir: E, to_path: *const u8, flags: mount::MoveMountFlags) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        429(from_dir.as_ref().raw(), from_path, to_dir.as_ref().raw(), to_path, flags) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Move a mount, or attach a detached mount, to a new location.
+/// 
+/// To attach a mount file descriptor, such as one returned by `fsmount`, pass it as `from_dir` with an empty
+/// `from_path` and `MoveMountFlags::FROM_EMPTY_PATH`.
+#[inline]
+#[cfg(feature = "std")]
+pub fn move_mount<'a, 'b, D: AsRef<Fd<'a>>, E: AsRef<Fd<'b>>, P: AsRef<std::path::Path>, Q: AsRef<std::path::Path>>(from_dir: D, from_path: P, to_dir: E, to_path: Q, flags: mount::MoveMountFlags) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let from_path = std::ffi::CString::new(from_path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    let to_path = std::ffi::CString::new(to_path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { move_mount_unsafe(from_dir, from_path.as_ptr() as *const u8, to_dir, to_path.as_ptr() as *const u8, flags) }
+}
+/// Open a file system context for a new instance of a file system type.
+/// 
+/// # Safety
+/// `fs_name` must be null-terminated.
+#[inline]
+pub unsafe fn fsopen_unsafe(fs_name: *const u8, flags: mount::FsopenFlags) -> Result<mount::FsContext, Error> {
+    let fd: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        430(fs_name, flags) -> fd
+    }
+    fd.try_into().map(mount::FsContext)
+}
+/// Open a file system context for a new instance of a file system type, such as `"tmpfs"`.
+#[inline]
+#[cfg(feature = "std")]
+pub fn fsopen(fs_name: &str, flags: mount::FsopenFlags) -> Result<mount::FsContext, Error> {
+    let fs_name = std::ffi::CString::new(fs_name.as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { fsopen_unsafe(fs_name.as_ptr() as *const u8, flags) }
+}
+/// Send a configuration command to a file system context.
+#[inline]
+pub fn fsconfig<'a, F: AsRef<Fd<'a>>>(context: F, config: mount::Config) -> Result<(), Error> {
+    let maybe: isize;
+    let (key, value, aux) = config.args();
+    unsafe {
+        syscall!{
+            431(context.as_ref().raw(), config.cmd(), key, value, aux) -> maybe
+        }
+    }
+    Error::maybe(maybe)
+}
+/// Create a detached mount from a configured file system context.
+#[inline]
+pub fn fsmount<'a, F: AsRef<Fd<'a>>>(context: F, flags: mount::FsmountFlags, attributes: mount::Attributes) -> Result<File, Error> {
+    let fd: isize;
+    let flags: u32 = flags.into();
+    let attributes: u64 = attributes.into();
+    unsafe {
+        syscall!{
+            432(context.as_ref().raw(), flags, attributes) -> fd
+        }
+    }
+    fd.try_into()
+}
+/// Open a file system context to reconfigure the superblock of an existing mount.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn fspick_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, flags: AtFlags) -> Result<mount::FsContext, Error> {
+    let fd: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        433(dir.as_ref().raw(), path, flags) -> fd
+    }
+    fd.try_into().map(mount::FsContext)
+}
+/// Open a file system context to reconfigure the superblock of an existing mount.
+#[inline]
+#[cfg(feature = "std")]
+pub fn fspick<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, flags: AtFlags) -> Result<mount::FsContext, Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { fspick_unsafe(dir, path.as_ptr() as *const u8, flags) }
+}
+/// Open a file descriptor referring to a process.
+#[inline]
+pub fn pidfd_open(pid: u32, flags: clone::PidfdFlags) -> Result<File, Error> {
+    let fd: isize;
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            434(pid, flags) -> fd
+        }
+    }
+    fd.try_into()
+}
+/// Create a child process.
+/// 
+/// Flags that share memory with the child, or need pointers in to it, are not supported and return `EINVAL`: `VM`,
+/// `THREAD`, `SIGNAL_HANDLERS`, `SET_TLS`, `PARENT_SET_TID`, `CHILD_SET_TID` and `CHILD_CLEAR_TID`. Use `vfork` to
+/// share memory. Falls back to the legacy `clone` on kernels without `clone3`, in which case arguments it cannot express
+/// return `ENOSYS`.
+/// 
+/// # Safety
+/// Only the calling thread is copied, so locks held by other threads stay held in the child. In a multi-threaded
+/// process the child must only make syscalls, and must not allocate, until it calls `execve` or `exit`.
+#[inline]
+pub unsafe fn clone3(args: &clone::CloneArgs) -> Result<clone::Fork, Error> {
+    use clone::Flags;
+    let unsupported = Flags::VM | Flags::THREAD | Flags::SIGNAL_HANDLERS | Flags::SET_TLS | Flags::PARENT_SET_TID
+        | Flags::CHILD_SET_TID | Flags::CHILD_CLEAR_TID;
+    if args.get_flags().any(unsupported) {
+        return Err(Error::EINVAL)
+    }
+    let pid: isize;
+    let mut pidfd = 0u32;
+    let raw = args.raw(&mut pidfd);
+    syscall!{
+        435(&raw as *const clone::RawCloneArgs, core::mem::size_of::<clone::RawCloneArgs>()) -> pid
+    }
+    match Error::maybe_u32(pid) {
+        Err(Error::ENOSYS) => clone_legacy(args),
+        pid => Ok(clone::Fork::new(pid?, args.get_flags().any(Flags::PIDFD).then_some(pidfd)))
+    }
+}
+/// Check whether the process can access a file relative to a directory.
+/// 
+/// `flags` may contain `EFFECTIVE_ACCESS` to check using the effective rather than the real user and group IDs,
+/// `SYMLINK_NO_FOLLOW` and `EMPTY_PATH`.
+/// 
+/// Kernels before 5.8 lack `faccessat2`, in which case the flags are emulated with `faccessat`. The emulation needs
+/// `/proc` for `SYMLINK_NO_FOLLOW` and `EMPTY_PATH`, and returns `ENOSYS` where it cannot give the same answer,
+/// such as `EFFECTIVE_ACCESS` in a set-user-ID program or checking anything but `AccessMode::EXISTS` on a symlink.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn faccessat2_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, mode: AccessMode, flags: AtFlags) -> Result<(), Error> {
+    let dir = dir.as_ref();
+    let maybe: isize;
+    let raw_mode: u32 = mode.into();
+    let raw_flags: u32 = flags.into();
+    syscall!{
+        439(dir.raw(), path, raw_mode, raw_flags) -> maybe
+    }
+    match Error::maybe(maybe) {
+        Err(Error::ENOSYS) => faccessat_emulated(dir, path, mode, flags),
+        result => result
+    }
+}
+/// Check whether the process can access a file relative to a directory.
+/// 
+/// `flags` may contain `EFFECTIVE_ACCESS` to check using the effective rather than the real user and group IDs,
+/// `SYMLINK_NO_FOLLOW` and `EMPTY_PATH`.
+/// 
+/// Kernels before 5.8 lack `faccessat2`, in which case the flags are emulated with `faccessat`. The emulation needs
+/// `/proc` for `SYMLINK_NO_FOLLOW` and `EMPTY_PATH`, and returns `ENOSYS` where it cannot give the same answer,
+/// such as `EFFECTIVE_ACCESS` in a set-user-ID program or checking anything but `AccessMode::EXISTS` on a symlink.
+#[inline]
+#[cfg(feature = "std")]
+pub fn faccessat2<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, mode: AccessMode, flags: AtFlags) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { faccessat2_unsafe(dir, path.as_ptr() as *const u8, mode, flags) }
+}
+/// Emulate `faccessat2` with `faccessat`, which takes no flags and uses the real user and group IDs.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+pub(crate) unsafe fn faccessat_emulated(dir: &Fd, path: *const u8, mode: AccessMode, flags: AtFlags) -> Result<(), Error> {
+    if flags.any(!(AtFlags::EFFECTIVE_ACCESS | AtFlags::SYMLINK_NO_FOLLOW | AtFlags::EMPTY_PATH)) {
+        return Err(Error::EINVAL)
+    }
+    if flags.any(AtFlags::EFFECTIVE_ACCESS) && (getuid() != geteuid() || getgid() != getegid()) {
+        return Err(Error::ENOSYS)
+    }
+    let empty = flags.any(AtFlags::EMPTY_PATH) && *path == 0;
+    if !empty && !flags.any(AtFlags::SYMLINK_NO_FOLLOW) {
+        return faccessat_unsafe(dir, path, mode)
+    }
+    if empty && dir.raw() == Fd::cwd.raw() {
+        return faccessat_unsafe(dir, c".".as_ptr() as *const u8, mode)
+    }
+    // Pin the file and check it through its magic link, so that it cannot be swapped for a symlink in between
+    let file;
+    let fd = if empty {
+        dir
+    } else {
+        let flags = open::Flags::PATH | open::Flags::NO_FOLLOW | open::Flags::CLOSE_ON_EXEC;
+        file = openat_unsafe(dir, path, flags, open::Mode::NONE)?;
+        file.as_ref()
+    };
+    if fstat(fd)?.file_type() == FileType::SYMLINK {
+        // Following the magic link of a symlink checks the symlink's target instead
+        return if mode == AccessMode::EXISTS { Ok(()) } else { Err(Error::ENOSYS) }
+    }
+    faccessat_unsafe(Fd::cwd, crate::fs::proc_fd_path(fd.raw()).as_ptr(), mode)
+}
+/// Give the kernel advice about how regions of memory in another process will be used.
+/// 
+/// Only non-destructive advice, `COLD`, `PAGE_OUT`, `WILL_NEED` and `COLLAPSE`, is accepted, otherwise `EINVAL` is
+/// returned. Recent kernels accept any advice when targeting the calling process, which could discard memory in use.
+/// Returns the number of bytes advised, which may be less than requested.
+#[inline]
+pub fn process_madvise<'a, F: AsRef<Fd<'a>>>(pidfd: F, ranges: &[RemoteIoVec], advice: mmap::Advice) -> Result<usize, Error> {
+    use mmap::Advice;
+    if !matches!(advice, Advice::COLD | Advice::PAGE_OUT | Advice::WILL_NEED | Advice::COLLAPSE) {
+        return Err(Error::EINVAL)
+    }
+    let count: isize;
+    let advice: u32 = advice.into();
+    unsafe {
+        syscall!{
+            440(pidfd.as_ref().raw(), ranges.as_ptr(), ranges.len(), advice, 0) -> count
+        }
+    }
+    Error::maybe_usize(count)
+}
+/// Change the properties of a mount, or of a mount tree with `AtFlags::RECURSIVE`.
+/// 
+/// # Safety
+/// `path` must be null-terminated.
+#[inline]
+pub unsafe fn mount_setattr_unsafe<'a, D: AsRef<Fd<'a>>>(dir: D, path: *const u8, flags: AtFlags, attr: &mount::MountAttr) -> Result<(), Error> {
+    let maybe: isize;
+    let flags: u32 = flags.into();
+    syscall!{
+        442(dir.as_ref().raw(), path, flags, attr as *const mount::MountAttr, core::mem::size_of::<mount::MountAttr>()) -> maybe
+    }
+    Error::maybe(maybe)
+}
+/// Change the properties of a mount, or of a mount tree with `AtFlags::RECURSIVE`.
+#[inline]
+#[cfg(feature = "std")]
+pub fn mount_setattr<'a, D: AsRef<Fd<'a>>, P: AsRef<std::path::Path>>(dir: D, path: P, flags: AtFlags, attr: &mount::MountAttr) -> Result<(), Error> {
+    use std::os::unix::prelude::OsStrExt;
+    let path = std::ffi::CString::new(path.as_ref().as_os_str().as_bytes()).map_err(|_| Error::EINVAL)?;
+    unsafe { mount_setattr_unsafe(dir, path.as_ptr() as *const u8, flags, attr) }
+}
+/// Create a file whose memory is only accessible to processes that map it, being removed from the kernel's own
+/// mappings.
+/// 
+/// Only `open::Flags::CLOSE_ON_EXEC` is accepted. The file must be sized with `File::truncate` before it is mapped, and
+/// its pages are locked in memory. Returns `ENOSYS` if the kernel does not support or has not enabled secret memory.
+#[inline]
+pub fn memfd_secret(flags: open::Flags) -> Result<File, Error> {
+    if flags & !open::Flags::CLOSE_ON_EXEC != open::Flags::NONE {
+        return Err(Error::EINVAL)
+    }
+    let fd: isize;
+    let flags: u32 = flags.into();
+    unsafe {
+        syscall!{
+            447(flags) -> fd
+        }
+    }
+    fd.try_into()
+}
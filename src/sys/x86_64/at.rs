@@ -0,0 +1,35 @@
+use crate::c_flags;
+
+c_flags!{
+    pub AtFlags(u32) {
+        SYMLINK_NO_FOLLOW = 0x100,
+        EFFECTIVE_ACCESS = 0x200,
+        SYMLINK_FOLLOW = 0x400,
+        NO_AUTOMOUNT = 0x800,
+        EMPTY_PATH = 0x1000,
+        RECURSIVE = 0x8000
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub UnlinkFlags(u32) {
+        REMOVE_DIR = 0x200
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub RenameFlags(u32) {
+        NO_REPLACE = 0x1,
+        EXCHANGE = 0x2,
+        WHITEOUT = 0x4
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub AccessMode(u32) {
+        EXISTS = 0,
+        EXECUTE = 1,
+        WRITE = 2,
+        READ = 4
+    } _ => Err(crate::Error::EINVAL)
+}
@@ -0,0 +1,188 @@
+use crate::{c_flags, Fd, File, FileDescriptor};
+
+/// The signal sent to the parent when a child exits, by default.
+pub const SIGCHLD: u32 = 17;
+
+c_flags!{
+    pub Flags(u64) {
+        NEW_TIME = 0x80,
+        VM = 0x100,
+        FS = 0x200,
+        FILES = 0x400,
+        SIGNAL_HANDLERS = 0x800,
+        PIDFD = 0x1000,
+        PTRACE = 0x2000,
+        VFORK = 0x4000,
+        PARENT = 0x8000,
+        THREAD = 0x1_0000,
+        NEW_MOUNT = 0x2_0000,
+        SYSV_SEMAPHORES = 0x4_0000,
+        SET_TLS = 0x8_0000,
+        PARENT_SET_TID = 0x10_0000,
+        CHILD_CLEAR_TID = 0x20_0000,
+        UNTRACED = 0x80_0000,
+        CHILD_SET_TID = 0x100_0000,
+        NEW_CGROUP = 0x200_0000,
+        NEW_UTS = 0x400_0000,
+        NEW_IPC = 0x800_0000,
+        NEW_USER = 0x1000_0000,
+        NEW_PID = 0x2000_0000,
+        NEW_NET = 0x4000_0000,
+        IO = 0x8000_0000,
+        CLEAR_SIGNAL_HANDLERS = 0x1_0000_0000,
+        INTO_CGROUP = 0x2_0000_0000
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub PidfdFlags(u32) {
+        THREAD = 0o200,
+        NON_BLOCKING = 0o4000
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub WaitFlags(u32) {
+        NO_HANG = 0x1,
+        UNTRACED = 0x2,
+        CONTINUED = 0x8,
+        ALL = 0x4000_0000,
+        CLONE = 0x8000_0000
+    } _ => Err(crate::Error::EINVAL)
+}
+
+/// The arguments of `clone3`.
+///
+/// By default the child gets a copy of the parent's resources and `SIGCHLD` is sent to the parent when it exits.
+#[derive(Debug, Clone, Copy)]
+pub struct CloneArgs<'a> {
+    flags: Flags,
+    exit_signal: u32,
+    set_tid: &'a [u32],
+    cgroup: Option<Fd<'a>>
+}
+impl<'a> Default for CloneArgs<'a> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<'a> CloneArgs<'a> {
+    pub fn new() -> Self {
+        Self {
+            flags: Flags::NONE,
+            exit_signal: SIGCHLD,
+            set_tid: &[],
+            cgroup: None
+        }
+    }
+    /// With `Flags::PIDFD`, a pidfd for the child is returned to the parent.
+    pub fn flags(mut self, flags: Flags) -> Self {
+        self.flags = flags;
+        self
+    }
+    /// The signal sent to the parent when the child exits, or zero for none.
+    pub fn exit_signal(mut self, signal: u32) -> Self {
+        self.exit_signal = signal;
+        self
+    }
+    /// Choose the PID of the child in each of its PID namespaces, innermost first. Requires `CAP_SYS_ADMIN` in the
+    /// user namespace owning each PID namespace.
+    pub fn set_tid(mut self, tids: &'a [u32]) -> Self {
+        self.set_tid = tids;
+        self
+    }
+    /// Start the child in a cgroup, given an open directory of the cgroup. `Flags::INTO_CGROUP` is added to the flags.
+    pub fn cgroup<F: AsRef<Fd<'a>>>(mut self, cgroup: F) -> Self {
+        self.cgroup = Some(*cgroup.as_ref());
+        self
+    }
+    pub(crate) fn get_flags(&self) -> Flags {
+        match self.cgroup {
+            Some(_) => self.flags | Flags::INTO_CGROUP,
+            None => self.flags
+        }
+    }
+    /// Whether the arguments can be passed to the legacy `clone`.
+    pub(crate) fn is_legacy(&self) -> bool {
+        self.set_tid.is_empty()
+            && self.cgroup.is_none()
+            && self.exit_signal <= 0xFF
+            && !self.get_flags().any(Flags::CLEAR_SIGNAL_HANDLERS | Flags::INTO_CGROUP)
+    }
+    pub(crate) fn legacy_flags(&self) -> u64 {
+        self.flags.0 | self.exit_signal as u64
+    }
+    pub(crate) fn raw(&self, pidfd: *mut u32) -> RawCloneArgs {
+        RawCloneArgs {
+            flags: self.get_flags().0,
+            pidfd: pidfd as u64,
+            exit_signal: self.exit_signal as u64,
+            // The kernel rejects a pointer without a length
+            set_tid: if self.set_tid.is_empty() { 0 } else { self.set_tid.as_ptr() as u64 },
+            set_tid_size: self.set_tid.len() as u64,
+            cgroup: self.cgroup.map_or(0, |cgroup| cgroup.raw() as u64),
+            ..RawCloneArgs::default()
+        }
+    }
+}
+
+/// `struct clone_args`.
+#[derive(Debug, Default)]
+#[repr(C)]
+pub(crate) struct RawCloneArgs {
+    pub(crate) flags: u64,
+    pub(crate) pidfd: u64,
+    pub(crate) child_tid: u64,
+    pub(crate) parent_tid: u64,
+    pub(crate) exit_signal: u64,
+    pub(crate) stack: u64,
+    pub(crate) stack_size: u64,
+    pub(crate) tls: u64,
+    pub(crate) set_tid: u64,
+    pub(crate) set_tid_size: u64,
+    pub(crate) cgroup: u64
+}
+
+/// Which side of a fork the caller is on.
+#[derive(Debug)]
+pub enum Fork {
+    Child,
+    Parent {
+        pid: u32,
+        /// The pidfd of the child, with `Flags::PIDFD`.
+        pidfd: Option<File>
+    }
+}
+
+impl Fork {
+    /// Interpret the return value of a fork, which is zero in the child.
+    pub(crate) fn new(pid: u32, pidfd: Option<u32>) -> Self {
+        match pid {
+            0 => Self::Child,
+            pid => Self::Parent { pid, pidfd: pidfd.map(|pidfd| Fd::from_raw(pidfd).owned()) }
+        }
+    }
+}
+
+/// How a child changed state, as reported by `wait4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WaitStatus(pub i32);
+impl WaitStatus {
+    /// The exit code, if the child exited.
+    pub fn exit_code(self) -> Option<i32> {
+        (self.0 & 0x7F == 0).then_some((self.0 >> 8) & 0xFF)
+    }
+    /// The signal that terminated the child, if it was killed.
+    pub fn signal(self) -> Option<u32> {
+        let signal = self.0 & 0x7F;
+        (signal != 0 && signal != 0x7F).then_some(signal as u32)
+    }
+    /// The signal that stopped the child, with `WaitFlags::UNTRACED`.
+    pub fn stop_signal(self) -> Option<u32> {
+        (self.0 & 0xFF == 0x7F).then_some(((self.0 >> 8) & 0xFF) as u32)
+    }
+    /// Whether the child was resumed, with `WaitFlags::CONTINUED`.
+    pub fn continued(self) -> bool {
+        self.0 == 0xFFFF
+    }
+}
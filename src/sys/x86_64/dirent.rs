@@ -0,0 +1,57 @@
+use crate::FileType;
+
+/// The size of a directory entry record, excluding the name.
+const ENTRY_LEN: usize = 19;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Entry<'a> {
+    pub inode: u64,
+    /// An opaque position in the directory, which may be passed to `lseek` to continue after this entry.
+    pub offset: i64,
+    /// The type of the file, which may be `UNKNOWN` if the file system does not provide it.
+    pub file_type: FileType,
+    pub name: &'a [u8]
+}
+impl<'a> Entry<'a> {
+    /// Returns true if this is the `.` or `..` entry.
+    pub fn is_dot(&self) -> bool {
+        self.name == b"." || self.name == b".."
+    }
+}
+
+/// An iterator over the variable-length records read by `getdents64`.
+#[derive(Debug, Clone)]
+pub struct Entries<'a>(&'a [u8]);
+impl<'a> Entries<'a> {
+    /// Iterate the entries in the filled portion of a `getdents64` buffer.
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self(buffer)
+    }
+}
+impl<'a> Iterator for Entries<'a> {
+    type Item = Entry<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.len() < ENTRY_LEN {
+            return None
+        }
+        let mut inode = [0; 8];
+        inode.copy_from_slice(&self.0[0..8]);
+        let mut offset = [0; 8];
+        offset.copy_from_slice(&self.0[8..16]);
+        let len = u16::from_ne_bytes([self.0[16], self.0[17]]) as usize;
+        if len < ENTRY_LEN || self.0.len() < len {
+            return None
+        }
+        // The name is null-terminated and padded for alignment
+        let name = &self.0[ENTRY_LEN..len];
+        let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(name.len())];
+        let entry = Entry {
+            inode: u64::from_ne_bytes(inode),
+            offset: i64::from_ne_bytes(offset),
+            file_type: FileType::from(self.0[18]),
+            name
+        };
+        self.0 = &self.0[len..];
+        Some(entry)
+    }
+}
@@ -0,0 +1,11 @@
+use crate::c_flags;
+
+c_flags!{
+    pub FallocateMode(u32) {
+        KEEP_SIZE = 0x01,
+        PUNCH_HOLE = 0x02,
+        COLLAPSE_RANGE = 0x08,
+        ZERO_RANGE = 0x10,
+        INSERT_RANGE = 0x20
+    } _ => Err(crate::Error::EINVAL)
+}
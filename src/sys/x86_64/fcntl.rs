@@ -1,11 +1,13 @@
-use crate::{Fd, open};
+use crate::{c_flags, Fd, open};
 
 pub enum Fcntl<'a> {
     DupFd(Fd<'a>),
     GetFd,
     SetFd(open::Flags),
     GetFl,
-    SetFl(open::Flags)
+    SetFl(open::Flags),
+    AddSeals(Seals),
+    GetSeals
 }
 impl<'a> Fcntl<'a> {
     pub fn cmd(&self) -> u32 {
@@ -14,7 +16,9 @@ impl<'a> Fcntl<'a> {
             Self::GetFd => 1,
             Self::SetFd(_) => 2,
             Self::GetFl => 3,
-            Self::SetFl(_) => 4
+            Self::SetFl(_) => 4,
+            Self::AddSeals(_) => 1033,
+            Self::GetSeals => 1034
         }
     }
     pub fn arg(&self) -> Option<usize> {
@@ -23,7 +27,19 @@ impl<'a> Fcntl<'a> {
             Self::GetFd => None,
             Self::SetFd(flags) => Some(flags.0 as _),
             Self::GetFl => None,
-            Self::SetFl(flags) => Some(flags.0 as _)
+            Self::SetFl(flags) => Some(flags.0 as _),
+            Self::AddSeals(seals) => Some(seals.0 as _),
+            Self::GetSeals => None
         }
     }
-}
\ No newline at end of file
+}
+c_flags!{
+    pub Seals(u32) {
+        SEAL = 0x1,
+        SHRINK = 0x2,
+        GROW = 0x4,
+        WRITE = 0x8,
+        FUTURE_WRITE = 0x10,
+        EXEC = 0x20
+    } _ => Err(crate::Error::EINVAL)
+}
@@ -0,0 +1,8 @@
+use crate::c_flags;
+
+c_flags!{
+    pub FutexFlags(u32) {
+        PRIVATE = 128,
+        CLOCK_REALTIME = 256
+    } _ => Err(crate::Error::EINVAL)
+}
@@ -0,0 +1,136 @@
+use crate::c_flags;
+
+c_flags!{
+    pub Flags(u32) {
+        NON_BLOCKING = 0o4000,
+        CLOSE_ON_EXEC = 0o2000000
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub WatchMask(u32) {
+        ACCESS = 0x1,
+        MODIFY = 0x2,
+        ATTRIBUTE = 0x4,
+        CLOSE_WRITE = 0x8,
+        CLOSE_NO_WRITE = 0x10,
+        OPEN = 0x20,
+        MOVED_FROM = 0x40,
+        MOVED_TO = 0x80,
+        CREATE = 0x100,
+        DELETE = 0x200,
+        DELETE_SELF = 0x400,
+        MOVE_SELF = 0x800,
+        UNMOUNT = 0x2000,
+        QUEUE_OVERFLOW = 0x4000,
+        IGNORED = 0x8000,
+        ONLY_DIRECTORY = 0x100_0000,
+        DONT_FOLLOW = 0x200_0000,
+        EXCLUDE_UNLINK = 0x400_0000,
+        MASK_CREATE = 0x1000_0000,
+        MASK_ADD = 0x2000_0000,
+        IS_DIRECTORY = 0x4000_0000,
+        ONE_SHOT = 0x8000_0000
+    } _ => Err(crate::Error::EINVAL)
+}
+impl WatchMask {
+    pub const CLOSE: Self = Self(Self::CLOSE_WRITE.0 | Self::CLOSE_NO_WRITE.0);
+    pub const MOVE: Self = Self(Self::MOVED_FROM.0 | Self::MOVED_TO.0);
+    pub const ALL_EVENTS: Self = Self(0xFFF);
+}
+
+/// A watch descriptor, identifying a watch added with `inotify_add_watch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Watch(pub i32);
+
+/// The size of an event record, excluding the name.
+const EVENT_LEN: usize = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Event<'a> {
+    pub wd: Watch,
+    pub mask: WatchMask,
+    /// Relates the `MOVED_FROM` and `MOVED_TO` events of a single rename.
+    pub cookie: u32,
+    /// The name of the entry within a watched directory, or `None` if the event is for the watched object itself.
+    pub name: Option<&'a [u8]>
+}
+
+/// An iterator over the variable-length event records read from an inotify file descriptor.
+#[derive(Debug, Clone)]
+pub struct Events<'a>(&'a [u8]);
+impl<'a> Events<'a> {
+    /// Iterate the events in the filled portion of a read buffer.
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self(buffer)
+    }
+    /// Combine each `MOVED_FROM` event with an immediately following `MOVED_TO` event of the same rename.
+    pub fn paired(self) -> Paired<'a> {
+        Paired { events: self, peeked: None }
+    }
+}
+impl<'a> Iterator for Events<'a> {
+    type Item = Event<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let field = |i: usize| u32::from_ne_bytes([self.0[i], self.0[i + 1], self.0[i + 2], self.0[i + 3]]);
+        if self.0.len() < EVENT_LEN {
+            return None
+        }
+        let len = field(12) as usize;
+        if self.0.len() < EVENT_LEN + len {
+            return None
+        }
+        let wd = Watch(field(0) as i32);
+        let mask = WatchMask(field(4));
+        let cookie = field(8);
+        // The name is padded with null bytes
+        let name = &self.0[EVENT_LEN..EVENT_LEN + len];
+        let name = &name[..name.iter().position(|&b| b == 0).unwrap_or(len)];
+        self.0 = &self.0[EVENT_LEN + len..];
+        Some(Event {
+            wd,
+            mask,
+            cookie,
+            name: if name.is_empty() { None } else { Some(name) }
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Notification<'a> {
+    Event(Event<'a>),
+    /// An entry was renamed between two watched directories, or within one.
+    Moved {
+        from: Event<'a>,
+        to: Event<'a>
+    }
+}
+
+/// An iterator that pairs the two halves of a rename.
+/// 
+/// The kernel usually queues the halves of a rename consecutively, but a `MOVED_FROM` event may be yielded alone if
+/// the entry was moved out of the watched directories or if the `MOVED_TO` event did not fit in the same read.
+#[derive(Debug, Clone)]
+pub struct Paired<'a> {
+    events: Events<'a>,
+    peeked: Option<Event<'a>>
+}
+impl<'a> Iterator for Paired<'a> {
+    type Item = Notification<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let event = self.peeked.take().or_else(|| self.events.next())?;
+        if !event.mask.any(WatchMask::MOVED_FROM) {
+            return Some(Notification::Event(event))
+        }
+        match self.events.next() {
+            Some(to) if to.mask.any(WatchMask::MOVED_TO) && to.cookie == event.cookie => {
+                Some(Notification::Moved { from: event, to })
+            },
+            next => {
+                self.peeked = next;
+                Some(Notification::Event(event))
+            }
+        }
+    }
+}
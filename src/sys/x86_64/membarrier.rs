@@ -0,0 +1,34 @@
+use crate::enumeration;
+
+enumeration!{
+    pub struct MembarrierCmd(u32) {
+        #["Query the supported commands"]
+        QUERY = 0,
+        #["Wait for a barrier on every thread in the system"]
+        GLOBAL = 1,
+        #["Barrier on the threads of processes registered for global expedited barriers"]
+        GLOBAL_EXPEDITED = 2,
+        #["Register the process for global expedited barriers"]
+        REGISTER_GLOBAL_EXPEDITED = 4,
+        #["Barrier on every running thread of the process"]
+        PRIVATE_EXPEDITED = 8,
+        #["Register the process for private expedited barriers"]
+        REGISTER_PRIVATE_EXPEDITED = 16,
+        #["Barrier on every running thread of the process, also serialising their instruction streams"]
+        PRIVATE_EXPEDITED_SYNC_CORE = 32,
+        #["Register the process for private expedited barriers that serialise instruction streams"]
+        REGISTER_PRIVATE_EXPEDITED_SYNC_CORE = 64,
+        #["Restart the restartable sequences running on threads of the process"]
+        PRIVATE_EXPEDITED_RSEQ = 128,
+        #["Register the process for restarting restartable sequences"]
+        REGISTER_PRIVATE_EXPEDITED_RSEQ = 256,
+        #["Query the commands the process is registered for"]
+        GET_REGISTRATIONS = 512
+    }
+}
+impl MembarrierCmd {
+    /// Whether this command is in a mask returned by `QUERY` or `GET_REGISTRATIONS`.
+    pub fn is_in(self, mask: u32) -> bool {
+        self.0 != 0 && mask & self.0 == self.0
+    }
+}
@@ -0,0 +1,23 @@
+use crate::c_flags;
+
+c_flags!{
+    pub MemfdFlags(u32) {
+        CLOSE_ON_EXEC = 0x1,
+        ALLOW_SEALING = 0x2,
+        HUGETLB = 0x4,
+        NO_EXEC_SEAL = 0x8,
+        EXEC = 0x10,
+        HUGE_64KB = 16 << 26,
+        HUGE_512KB = 19 << 26,
+        HUGE_1MB = 20 << 26,
+        HUGE_2MB = 21 << 26,
+        HUGE_8MB = 23 << 26,
+        HUGE_16MB = 24 << 26,
+        HUGE_32MB = 25 << 26,
+        HUGE_256MB = 28 << 26,
+        HUGE_512MB = 29 << 26,
+        HUGE_1GB = 30 << 26,
+        HUGE_2GB = 31 << 26,
+        HUGE_16GB = 34 << 26
+    } _ => Err(crate::Error::EINVAL)
+}
@@ -0,0 +1,126 @@
+use crate::{c_flags, enumeration};
+
+/// The number of nodes a `NodeMask` can hold, which is the most the kernel supports.
+pub const MAX_NODES: usize = 1024;
+
+enumeration!{
+    pub struct MemPolicy(u32) {
+        #["Use the policy of the process, or of the system for the process"]
+        DEFAULT = 0,
+        #["Allocate on the given node, falling back to others"]
+        PREFERRED = 1,
+        #["Allocate only on the given nodes"]
+        BIND = 2,
+        #["Interleave allocations across the given nodes"]
+        INTERLEAVE = 3,
+        #["Allocate on the node of the CPU that triggers the allocation"]
+        LOCAL = 4,
+        #["Allocate on any of the given nodes, falling back to others"]
+        PREFERRED_MANY = 5,
+        #["Interleave allocations across the given nodes, weighted by node"]
+        WEIGHTED_INTERLEAVE = 6
+    }
+}
+
+c_flags!{
+    pub ModeFlags(u32) {
+        NUMA_BALANCING = 1 << 13,
+        RELATIVE_NODES = 1 << 14,
+        STATIC_NODES = 1 << 15
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub GetFlags(u32) {
+        NODE = 0x1,
+        ADDRESS = 0x2,
+        MEMS_ALLOWED = 0x4
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub MoveFlags(u32) {
+        STRICT = 0x1,
+        MOVE = 0x2,
+        MOVE_ALL = 0x4
+    } _ => Err(crate::Error::EINVAL)
+}
+
+/// Split a mode returned by `get_mempolicy` in to its policy and flags.
+pub fn split_mode(mode: u32) -> (MemPolicy, ModeFlags) {
+    (MemPolicy::from(mode & !ModeFlags::MASK.0), ModeFlags(mode & ModeFlags::MASK.0))
+}
+
+/// A set of NUMA nodes.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct NodeMask([u64; MAX_NODES / 64]);
+impl NodeMask {
+    pub const fn new() -> Self {
+        Self([0; MAX_NODES / 64])
+    }
+    /// Add a node, returning whether it was newly added.
+    ///
+    /// # Panics
+    /// Panics if `node` is not less than `MAX_NODES`.
+    pub fn insert(&mut self, node: u32) -> bool {
+        let (word, bit) = Self::position(node);
+        let added = self.0[word] & bit == 0;
+        self.0[word] |= bit;
+        added
+    }
+    /// Remove a node, returning whether it was present.
+    pub fn remove(&mut self, node: u32) -> bool {
+        if node as usize >= MAX_NODES {
+            return false
+        }
+        let (word, bit) = Self::position(node);
+        let present = self.0[word] & bit != 0;
+        self.0[word] &= !bit;
+        present
+    }
+    pub fn contains(&self, node: u32) -> bool {
+        node < MAX_NODES as u32 && {
+            let (word, bit) = Self::position(node);
+            self.0[word] & bit != 0
+        }
+    }
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|&word| word == 0)
+    }
+    /// The number of nodes in the set.
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+    /// Iterate the nodes in ascending order.
+    pub fn nodes(&self) -> impl Iterator<Item = u32> + '_ {
+        (0..MAX_NODES as u32).filter(|&node| self.contains(node))
+    }
+    fn position(node: u32) -> (usize, u64) {
+        assert!((node as usize) < MAX_NODES, "node {node} is out of range");
+        (node as usize / 64, 1 << (node % 64))
+    }
+    /// The `maxnode` argument for the whole mask. The kernel ignores the last bit it is told of.
+    pub(crate) const fn max_node() -> usize {
+        MAX_NODES + 1
+    }
+}
+impl Default for NodeMask {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl FromIterator<u32> for NodeMask {
+    fn from_iter<I: IntoIterator<Item = u32>>(nodes: I) -> Self {
+        let mut mask = Self::new();
+        for node in nodes {
+            mask.insert(node);
+        }
+        mask
+    }
+}
+impl core::fmt::Debug for NodeMask {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_set().entries(self.nodes()).finish()
+    }
+}
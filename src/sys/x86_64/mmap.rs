@@ -1,24 +1,255 @@
-use crate::c_flags;
+use crate::{c_flags, enumeration, Error, Fd};
 
 pub const MAP_FAILED: *mut core::ffi::c_void = usize::MAX as *mut _;
+/// The size of a base page. File offsets must be a multiple of this size.
+pub const PAGE_SIZE: usize = 4096;
 
 c_flags!{
     pub Flags(u32) {
-        SHARED = 1,
-        PRIVATE = 2
+        SHARED = 0x1,
+        PRIVATE = 0x2,
+        SHARED_VALIDATE = 0x3,
+        FIXED = 0x10,
+        ANONYMOUS = 0x20,
+        GROWS_DOWN = 0x100,
+        LOCKED = 0x2000,
+        NO_RESERVE = 0x4000,
+        POPULATE = 0x8000,
+        STACK = 0x20000,
+        HUGETLB = 0x40000,
+        SYNC = 0x80000,
+        FIXED_NO_REPLACE = 0x100000,
+        HUGE_2MB = 21 << 26,
+        HUGE_1GB = 30 << 26
     } _ => Err(crate::Error::EINVAL)
 }
 
 c_flags!{
     pub Protection(u32) {
-        EXEC = 0b001,
-        READ = 0b010,
-        WRITE = 0b100
+        READ = 0b001,
+        WRITE = 0b010,
+        EXEC = 0b100
     } _ => Err(crate::Error::EINVAL)
 }
 
 c_flags!{
     pub RemapFlags(u32) {
-        MAY_MOVE = 0b001
+        MAY_MOVE = 0b001,
+        FIXED = 0b010
     } _ => Err(crate::Error::EINVAL)
-}
\ No newline at end of file
+}
+
+enumeration!{
+    pub struct Advice(u32) {
+        #["No special treatment"]
+        NORMAL = 0,
+        #["Expect random access"]
+        RANDOM = 1,
+        #["Expect sequential access"]
+        SEQUENTIAL = 2,
+        #["Expect access soon"]
+        WILL_NEED = 3,
+        #["Do not expect access soon"]
+        DONT_NEED = 4,
+        #["Free pages lazily"]
+        FREE = 8,
+        #["Free the backing store"]
+        REMOVE = 9,
+        #["Do not inherit across fork"]
+        DONT_FORK = 10,
+        #["Inherit across fork"]
+        DO_FORK = 11,
+        #["Enable same-page merging"]
+        MERGEABLE = 12,
+        #["Disable same-page merging"]
+        UNMERGEABLE = 13,
+        #["Enable transparent huge pages"]
+        HUGE_PAGE = 14,
+        #["Disable transparent huge pages"]
+        NO_HUGE_PAGE = 15,
+        #["Exclude from core dumps"]
+        DONT_DUMP = 16,
+        #["Include in core dumps"]
+        DO_DUMP = 17,
+        #["Zero in the child after fork"]
+        WIPE_ON_FORK = 18,
+        #["Keep in the child after fork"]
+        KEEP_ON_FORK = 19,
+        #["Deactivate pages"]
+        COLD = 20,
+        #["Reclaim pages"]
+        PAGE_OUT = 21,
+        #["Prefault pages readable"]
+        POPULATE_READ = 22,
+        #["Prefault pages writable"]
+        POPULATE_WRITE = 23,
+        #["Free locked pages"]
+        DONT_NEED_LOCKED = 24,
+        #["Collapse in to transparent huge pages"]
+        COLLAPSE = 25
+    }
+}
+
+c_flags!{
+    pub MsyncFlags(u32) {
+        ASYNC = 0x1,
+        INVALIDATE = 0x2,
+        SYNC = 0x4
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub LockFlags(u32) {
+        ON_FAULT = 0x1
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub LockAllFlags(u32) {
+        CURRENT = 0x1,
+        FUTURE = 0x2,
+        ON_FAULT = 0x4
+    } _ => Err(crate::Error::EINVAL)
+}
+
+/// An owned memory mapping, which is unmapped on drop.
+/// 
+/// The mapping derefs to `[u8]`, panicking if it is not readable.
+pub struct Mapping {
+    address: core::ptr::NonNull<u8>,
+    length: usize,
+    protection: Protection
+}
+// Safety: the mapping is owned memory, as with a `Vec<u8>`
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+impl Mapping {
+    /// Map zeroed, private memory that is readable and writable.
+    pub fn anonymous(length: usize) -> Result<Self, Error> {
+        let protection = Protection::READ | Protection::WRITE;
+        let address = crate::mmap_anonymous(0, length, protection, Flags::PRIVATE)?;
+        Ok(unsafe { Self::from_raw(address, length, protection) })
+    }
+    /// Map a range of a file. The start of the range must be a multiple of the page size.
+    /// 
+    /// Use `Flags::SHARED` for writes to reach the file and to observe writes by others.
+    /// 
+    /// # Safety
+    /// The mapped range of the file must not be modified while it is borrowed as a slice, including by other processes
+    /// through a shared mapping. Accessing pages beyond the end of the file, such as after it is truncated, raises `SIGBUS`.
+    pub unsafe fn from_file<'a, F: AsRef<Fd<'a>>>(file: F, range: core::ops::Range<usize>, protection: Protection, flags: Flags) -> Result<Self, Error> {
+        let length = range.end.checked_sub(range.start).ok_or(Error::EINVAL)?;
+        let address = crate::mmap(0, length, protection, flags, file, range.start)?;
+        Ok(Self::from_raw(address, length, protection))
+    }
+    /// Take ownership of an existing mapping.
+    /// 
+    /// # Safety
+    /// The mapping must be owned by the caller, must span `length` bytes and must have the given protection.
+    pub unsafe fn from_raw(address: *mut core::ffi::c_void, length: usize, protection: Protection) -> Self {
+        Self {
+            address: core::ptr::NonNull::new_unchecked(address as *mut u8),
+            length,
+            protection
+        }
+    }
+    /// Give up ownership of the mapping without unmapping it.
+    pub fn into_raw(self) -> (*mut core::ffi::c_void, usize) {
+        let raw = (self.as_ptr() as *mut core::ffi::c_void, self.length);
+        core::mem::forget(self);
+        raw
+    }
+    pub fn as_ptr(&self) -> *mut u8 {
+        self.address.as_ptr()
+    }
+    pub fn len(&self) -> usize {
+        self.length
+    }
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+    pub fn protection(&self) -> Protection {
+        self.protection
+    }
+    /// The mapped memory, or `EACCES` if the mapping is not readable.
+    pub fn as_slice(&self) -> Result<&[u8], Error> {
+        if !self.protection.any(Protection::READ) {
+            return Err(Error::EACCES)
+        }
+        Ok(unsafe { core::slice::from_raw_parts(self.as_ptr(), self.length) })
+    }
+    /// The mapped memory, or `EACCES` if the mapping is not writable.
+    pub fn as_mut_slice(&mut self) -> Result<&mut [u8], Error> {
+        if !self.protection.all(Protection::READ | Protection::WRITE) {
+            return Err(Error::EACCES)
+        }
+        Ok(unsafe { core::slice::from_raw_parts_mut(self.as_ptr(), self.length) })
+    }
+    /// Change the access protections of the mapping.
+    pub fn protect(&mut self, protection: Protection) -> Result<(), Error> {
+        unsafe { crate::mprotect(self.as_ptr() as *mut _, self.length, protection)? };
+        self.protection = protection;
+        Ok(())
+    }
+    /// Give the kernel advice about how the mapping will be used.
+    /// 
+    /// Advice such as `DONT_NEED` may discard the contents of the mapping.
+    pub fn advise(&mut self, advice: Advice) -> Result<(), Error> {
+        unsafe { crate::madvise(self.as_ptr() as *mut _, self.length, advice) }
+    }
+    /// Flush changes to a shared file mapping back to the file.
+    pub fn sync(&self, flags: MsyncFlags) -> Result<(), Error> {
+        crate::msync(self.as_ptr() as *mut _, self.length, flags)
+    }
+    /// Get which pages of the mapping are resident in memory. See `mincore`.
+    pub fn residency<'a>(&self, residency: &'a mut [u8]) -> Result<&'a [u8], Error> {
+        crate::mincore(self.as_ptr() as *mut _, self.length, residency)
+    }
+    /// Lock the pages of the mapping in to memory.
+    pub fn lock(&self, flags: LockFlags) -> Result<(), Error> {
+        crate::mlock2(self.as_ptr() as *const _, self.length, flags)
+    }
+    /// Allow the pages of the mapping to be paged out again.
+    pub fn unlock(&self) -> Result<(), Error> {
+        crate::munlock(self.as_ptr() as *const _, self.length)
+    }
+    /// Grow or shrink the mapping, moving it if it cannot be resized in place.
+    pub fn resize(&mut self, length: usize) -> Result<(), Error> {
+        let address = unsafe { crate::mremap(self.as_ptr() as *mut _, self.length, length, RemapFlags::MAY_MOVE, core::ptr::null_mut())? };
+        self.address = unsafe { core::ptr::NonNull::new_unchecked(address as *mut u8) };
+        self.length = length;
+        Ok(())
+    }
+    /// Move the mapping to `address`, resizing it to `length`.
+    /// 
+    /// # Safety
+    /// Any existing mapping overlapping the new location is unmapped, which must not invalidate memory still in use.
+    pub unsafe fn resize_at(&mut self, address: *mut core::ffi::c_void, length: usize) -> Result<(), Error> {
+        let address = crate::mremap(self.as_ptr() as *mut _, self.length, length, RemapFlags::MAY_MOVE | RemapFlags::FIXED, address)?;
+        self.address = core::ptr::NonNull::new_unchecked(address as *mut u8);
+        self.length = length;
+        Ok(())
+    }
+}
+impl core::ops::Deref for Mapping {
+    type Target = [u8];
+    /// # Panics
+    /// Panics if the mapping is not readable.
+    fn deref(&self) -> &Self::Target {
+        self.as_slice().expect("the mapping is not readable")
+    }
+}
+impl core::fmt::Debug for Mapping {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("Mapping")
+            .field("address", &self.address)
+            .field("length", &self.length)
+            .field("protection", &self.protection)
+            .finish()
+    }
+}
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        let _ = unsafe { crate::munmap(self.as_ptr() as *mut _, self.length) };
+    }
+}
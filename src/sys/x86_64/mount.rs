@@ -0,0 +1,246 @@
+use core::ffi::CStr;
+use crate::{c_flags, enumeration, Error, Fd, File, FileDescriptor};
+
+c_flags!{
+    pub MountFlags(u64) {
+        READ_ONLY = 0x1,
+        NO_SETUID = 0x2,
+        NO_DEVICE = 0x4,
+        NO_EXEC = 0x8,
+        SYNCHRONOUS = 0x10,
+        REMOUNT = 0x20,
+        MANDATORY_LOCK = 0x40,
+        DIRECTORY_SYNC = 0x80,
+        NO_SYMLINK_FOLLOW = 0x100,
+        NO_ACCESS_TIME = 0x400,
+        NO_DIRECTORY_ACCESS_TIME = 0x800,
+        BIND = 0x1000,
+        MOVE = 0x2000,
+        RECURSIVE = 0x4000,
+        SILENT = 0x8000,
+        POSIX_ACL = 0x1_0000,
+        UNBINDABLE = 0x2_0000,
+        PRIVATE = 0x4_0000,
+        SLAVE = 0x8_0000,
+        SHARED = 0x10_0000,
+        RELATIVE_ACCESS_TIME = 0x20_0000,
+        I_VERSION = 0x80_0000,
+        STRICT_ACCESS_TIME = 0x100_0000,
+        LAZY_TIME = 0x200_0000
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub UnmountFlags(u32) {
+        FORCE = 0x1,
+        DETACH = 0x2,
+        EXPIRE = 0x4,
+        NO_FOLLOW = 0x8
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub FsopenFlags(u32) {
+        CLOSE_ON_EXEC = 0x1
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub FsmountFlags(u32) {
+        CLOSE_ON_EXEC = 0x1
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub OpenTreeFlags(u32) {
+        CLONE = 0x1,
+        SYMLINK_NO_FOLLOW = 0x100,
+        NO_AUTOMOUNT = 0x800,
+        EMPTY_PATH = 0x1000,
+        RECURSIVE = 0x8000,
+        CLOSE_ON_EXEC = 0o2000000
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub MoveMountFlags(u32) {
+        FROM_SYMLINKS = 0x1,
+        FROM_AUTOMOUNTS = 0x2,
+        FROM_EMPTY_PATH = 0x4,
+        TO_SYMLINKS = 0x10,
+        TO_AUTOMOUNTS = 0x20,
+        TO_EMPTY_PATH = 0x40,
+        SET_GROUP = 0x100,
+        BENEATH = 0x200
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub Attributes(u64) {
+        READ_ONLY = 0x1,
+        NO_SETUID = 0x2,
+        NO_DEVICE = 0x4,
+        NO_EXEC = 0x8,
+        NO_ACCESS_TIME = 0x10,
+        STRICT_ACCESS_TIME = 0x20,
+        NO_DIRECTORY_ACCESS_TIME = 0x80,
+        ID_MAP = 0x10_0000,
+        NO_SYMLINK_FOLLOW = 0x20_0000
+    } _ => Err(crate::Error::EINVAL)
+}
+impl Attributes {
+    /// The bits selecting the access time behaviour. The default of zero is relative access times.
+    pub const ACCESS_TIME: Self = Self(0x70);
+}
+
+enumeration!{
+    pub struct Propagation(u64) {
+        #["Propagation is unchanged"]
+        UNCHANGED = 0,
+        #["The mount cannot be bind mounted"]
+        UNBINDABLE = 0x20000,
+        #["Mount events are not propagated"]
+        PRIVATE = 0x40000,
+        #["Mount events are received from the peer group but not sent"]
+        SLAVE = 0x80000,
+        #["Mount events are shared with the peer group"]
+        SHARED = 0x100000
+    }
+}
+
+/// Changes to the properties of a mount, applied with `mount_setattr`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct MountAttr {
+    pub set: Attributes,
+    pub clear: Attributes,
+    pub propagation: Propagation,
+    pub user_namespace: u64
+}
+impl Default for MountAttr {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl MountAttr {
+    /// No changes.
+    pub const fn new() -> Self {
+        Self {
+            set: Attributes::NONE,
+            clear: Attributes::NONE,
+            propagation: Propagation::UNCHANGED,
+            user_namespace: 0
+        }
+    }
+    /// Set the attributes on the mount.
+    pub fn set(mut self, attributes: Attributes) -> Self {
+        self.set |= attributes;
+        self
+    }
+    /// Clear the attributes from the mount.
+    /// 
+    /// To change the access time behaviour `ACCESS_TIME` must be cleared.
+    pub fn clear(mut self, attributes: Attributes) -> Self {
+        self.clear |= attributes;
+        self
+    }
+    pub fn propagation(mut self, propagation: Propagation) -> Self {
+        self.propagation = propagation;
+        self
+    }
+    /// Create an ID-mapped mount using the ID mappings of the user namespace referred to by `user_namespace`.
+    pub fn id_map<'a, F: AsRef<Fd<'a>>>(mut self, user_namespace: F) -> Self {
+        self.set |= Attributes::ID_MAP;
+        self.user_namespace = user_namespace.as_ref().raw() as u64;
+        self
+    }
+}
+
+/// A command to configure a file system context.
+/// 
+/// Keys and values are file system specific, as for the options of `mount`.
+pub enum Config<'a> {
+    /// Set a boolean parameter, such as `ro`.
+    SetFlag(&'a CStr),
+    SetString(&'a CStr, &'a CStr),
+    SetBinary(&'a CStr, &'a [u8]),
+    /// Set a parameter to a path relative to a directory, such as `source`.
+    SetPath(&'a CStr, Fd<'a>, &'a CStr),
+    /// Set a parameter to the file referred to by a file descriptor, without a path.
+    SetPathEmpty(&'a CStr, Fd<'a>),
+    SetFd(&'a CStr, Fd<'a>),
+    /// Create the superblock, sharing an existing superblock if the file system allows.
+    Create,
+    /// Apply the parameters to an existing superblock opened with `fspick`.
+    Reconfigure,
+    /// Create a new superblock, failing with `EBUSY` rather than sharing an existing one.
+    CreateExclusive
+}
+impl<'a> Config<'a> {
+    pub fn cmd(&self) -> u32 {
+        match self {
+            Self::SetFlag(_) => 0,
+            Self::SetString(_, _) => 1,
+            Self::SetBinary(_, _) => 2,
+            Self::SetPath(_, _, _) => 3,
+            Self::SetPathEmpty(_, _) => 4,
+            Self::SetFd(_, _) => 5,
+            Self::Create => 6,
+            Self::Reconfigure => 7,
+            Self::CreateExclusive => 8
+        }
+    }
+    /// The key, value and auxiliary arguments of the command.
+    pub fn args(&self) -> (*const u8, *const u8, usize) {
+        let null = core::ptr::null();
+        match self {
+            Self::SetFlag(key) => (key.as_ptr() as _, null, 0),
+            Self::SetString(key, value) => (key.as_ptr() as _, value.as_ptr() as _, 0),
+            Self::SetBinary(key, value) => (key.as_ptr() as _, value.as_ptr(), value.len()),
+            Self::SetPath(key, dir, path) => (key.as_ptr() as _, path.as_ptr() as _, dir.raw() as _),
+            Self::SetPathEmpty(key, fd) => (key.as_ptr() as _, c"".as_ptr() as _, fd.raw() as _),
+            Self::SetFd(key, fd) => (key.as_ptr() as _, null, fd.raw() as _),
+            Self::Create | Self::Reconfigure | Self::CreateExclusive => (null, null, 0)
+        }
+    }
+}
+
+/// A file system context, used to configure and create a new mount.
+/// 
+/// Created with `fsopen` or `fspick`.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct FsContext(pub(crate) File);
+impl FsContext {
+    /// Send a configuration command to the file system context.
+    #[inline]
+    pub fn config(&self, config: Config) -> Result<(), Error> {
+        crate::fsconfig(self, config)
+    }
+    /// Create a detached mount of the configured file system.
+    /// 
+    /// The context must have been created with `Config::Create`.
+    #[inline]
+    pub fn mount(&self, flags: FsmountFlags, attributes: Attributes) -> Result<File, Error> {
+        crate::fsmount(self, flags, attributes)
+    }
+    /// Read the next error, warning or informational message logged by the file system.
+    /// 
+    /// Fails with `ENODATA` once there are no more messages.
+    #[inline]
+    pub fn message<'a>(&self, buffer: &'a mut [u8]) -> Result<&'a [u8], Error> {
+        crate::read(self, buffer)
+    }
+}
+impl FileDescriptor for FsContext {
+    #[inline(always)]
+    fn raw(&self) -> u32 {
+        self.0.raw()
+    }
+}
+impl<'a> AsRef<Fd<'a>> for FsContext {
+    fn as_ref(&self) -> &'a Fd<'a> {
+        // Safety: FsContext, File and Fd are all `repr(transparent)` over u32
+        unsafe { core::mem::transmute(self) }
+    }
+}
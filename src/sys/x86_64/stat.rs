@@ -1,3 +1,26 @@
+use crate::enumeration;
+
+enumeration!{
+    pub struct FileType(u8) {
+        #["Unknown file type"]
+        UNKNOWN = 0,
+        #["Named pipe"]
+        FIFO = 1,
+        #["Character device"]
+        CHARACTER_DEVICE = 2,
+        #["Directory"]
+        DIRECTORY = 4,
+        #["Block device"]
+        BLOCK_DEVICE = 6,
+        #["Regular file"]
+        REGULAR = 8,
+        #["Symbolic link"]
+        SYMLINK = 10,
+        #["Unix domain socket"]
+        SOCKET = 12
+    }
+}
+
 #[derive(Clone, Copy)]
 #[repr(transparent)]
 pub struct Device(u64);
@@ -53,6 +76,12 @@ pub struct Stat {
 	pub status_change_time_nsec: u64,
 	_pad2: [u64; 3]
 }
+impl Stat {
+    /// The type of the file, from the upper bits of the mode.
+    pub fn file_type(&self) -> FileType {
+        FileType(((self.mode & 0o170000) >> 12) as u8)
+    }
+}
 impl core::fmt::Debug for Stat {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         f.debug_struct("Stat")
@@ -0,0 +1,173 @@
+use crate::{c_flags, enumeration};
+
+c_flags!{
+    pub MountFlags(u64) {
+        READ_ONLY = 0x1,
+        NO_SETUID = 0x2,
+        NO_DEVICE = 0x4,
+        NO_EXEC = 0x8,
+        SYNCHRONOUS = 0x10,
+        VALID = 0x20,
+        MANDATORY_LOCK = 0x40,
+        NO_ACCESS_TIME = 0x400,
+        NO_DIRECTORY_ACCESS_TIME = 0x800,
+        RELATIVE_ACCESS_TIME = 0x1000,
+        NO_SYMLINK_FOLLOW = 0x2000
+    } _ => Err(crate::Error::EINVAL)
+}
+
+enumeration!{
+    pub struct FsMagic(i64) {
+        #["ADFS"]
+        ADFS = 0xadf5,
+        #["Amiga FFS"]
+        AFFS = 0xadff,
+        #["AFS"]
+        AFS = 0x5346414f,
+        #["Anonymous inodes"]
+        ANON_INODE_FS = 0x09041934,
+        #["autofs"]
+        AUTOFS = 0x0187,
+        #["bcachefs"]
+        BCACHEFS = 0xca451a4e,
+        #["Block devices"]
+        BDEVFS = 0x62646576,
+        #["binfmt_misc"]
+        BINFMTFS = 0x42494e4d,
+        #["BPF file system"]
+        BPF_FS = 0xcafe4a11,
+        #["Btrfs"]
+        BTRFS = 0x9123683e,
+        #["Ceph"]
+        CEPH = 0x00c36400,
+        #["cgroup"]
+        CGROUP = 0x0027e0eb,
+        #["cgroup2"]
+        CGROUP2 = 0x63677270,
+        #["CIFS"]
+        CIFS = 0xff534d42,
+        #["configfs"]
+        CONFIGFS = 0x62656570,
+        #["cramfs"]
+        CRAMFS = 0x28cd3d45,
+        #["debugfs"]
+        DEBUGFS = 0x64626720,
+        #["devpts"]
+        DEVPTS = 0x1cd1,
+        #["eCryptfs"]
+        ECRYPTFS = 0xf15f,
+        #["efivarfs"]
+        EFIVARFS = 0xde5e81e4,
+        #["EROFS"]
+        EROFS = 0xe0f5e1e2,
+        #["exFAT"]
+        EXFAT = 0x2011bab0,
+        #["ext2, ext3 or ext4"]
+        EXT4 = 0xef53,
+        #["F2FS"]
+        F2FS = 0xf2f52010,
+        #["FUSE"]
+        FUSE = 0x65735546,
+        #["GFS2"]
+        GFS2 = 0x01161970,
+        #["hugetlbfs"]
+        HUGETLBFS = 0x958458f6,
+        #["ISO 9660"]
+        ISOFS = 0x9660,
+        #["JFFS2"]
+        JFFS2 = 0x72b6,
+        #["JFS"]
+        JFS = 0x3153464a,
+        #["POSIX message queues"]
+        MQUEUE = 0x19800202,
+        #["FAT"]
+        MSDOS = 0x4d44,
+        #["NFS"]
+        NFS = 0x6969,
+        #["NILFS"]
+        NILFS = 0x3434,
+        #["Namespaces"]
+        NSFS = 0x6e736673,
+        #["NTFS"]
+        NTFS = 0x5346544e,
+        #["OCFS2"]
+        OCFS2 = 0x7461636f,
+        #["overlayfs"]
+        OVERLAYFS = 0x794c7630,
+        #["Pipes"]
+        PIPEFS = 0x50495045,
+        #["procfs"]
+        PROC = 0x9fa0,
+        #["pstore"]
+        PSTOREFS = 0x6165676c,
+        #["ramfs"]
+        RAMFS = 0x858458f6,
+        #["ReiserFS"]
+        REISERFS = 0x52654973,
+        #["Secret memory"]
+        SECRETMEM = 0x5345434d,
+        #["securityfs"]
+        SECURITYFS = 0x73636673,
+        #["selinuxfs"]
+        SELINUX = 0xf97cff8c,
+        #["smackfs"]
+        SMACK = 0x43415d53,
+        #["SMB2"]
+        SMB2 = 0xfe534d42,
+        #["Sockets"]
+        SOCKFS = 0x534f434b,
+        #["SquashFS"]
+        SQUASHFS = 0x73717368,
+        #["sysfs"]
+        SYSFS = 0x62656572,
+        #["tmpfs"]
+        TMPFS = 0x01021994,
+        #["tracefs"]
+        TRACEFS = 0x74726163,
+        #["UDF"]
+        UDF = 0x15013346,
+        #["9P"]
+        V9FS = 0x01021997,
+        #["XFS"]
+        XFS = 0x58465342,
+        #["ZFS"]
+        ZFS = 0x2fc12fc1,
+        #["zonefs"]
+        ZONEFS = 0x5a4f4653
+    }
+}
+
+#[derive(Clone)]
+#[repr(C)]
+pub struct StatFs {
+    pub file_system: FsMagic,
+    pub block_size: i64,
+    pub blocks: u64,
+    pub blocks_free: u64,
+    /// Free blocks available to unprivileged users.
+    pub blocks_available: u64,
+    pub files: u64,
+    pub files_free: u64,
+    pub id: [i32; 2],
+    pub name_len: i64,
+    pub fragment_size: i64,
+    pub flags: MountFlags,
+    _spare: [i64; 4]
+}
+impl core::fmt::Debug for StatFs {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("StatFs")
+            .field("file_system", &self.file_system)
+            .field("block_size", &self.block_size)
+            .field("blocks", &self.blocks)
+            .field("blocks_free", &self.blocks_free)
+            .field("blocks_available", &self.blocks_available)
+            .field("files", &self.files)
+            .field("files_free", &self.files_free)
+            .field("id", &self.id)
+            .field("name_len", &self.name_len)
+            .field("fragment_size", &self.fragment_size)
+            .field("flags", &self.flags)
+            .finish()
+    }
+}
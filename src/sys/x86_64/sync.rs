@@ -0,0 +1,9 @@
+use crate::c_flags;
+
+c_flags!{
+    pub SyncFileRangeFlags(u32) {
+        WAIT_BEFORE = 0x1,
+        WRITE = 0x2,
+        WAIT_AFTER = 0x4
+    } _ => Err(crate::Error::EINVAL)
+}
@@ -0,0 +1,43 @@
+use crate::enumeration;
+
+/// Set a timestamp to the current time.
+pub const UTIME_NOW: i64 = (1 << 30) - 1;
+/// Leave a timestamp unchanged.
+pub const UTIME_OMIT: i64 = (1 << 30) - 2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct Timespec {
+    pub seconds: i64,
+    pub nanoseconds: i64
+}
+impl Timespec {
+    /// When used as a file timestamp, set the timestamp to the current time.
+    pub const NOW: Self = Self { seconds: 0, nanoseconds: UTIME_NOW };
+    /// When used as a file timestamp, leave the timestamp unchanged.
+    pub const OMIT: Self = Self { seconds: 0, nanoseconds: UTIME_OMIT };
+    pub const fn new(seconds: i64, nanoseconds: i64) -> Self {
+        Self { seconds, nanoseconds }
+    }
+}
+
+enumeration!{
+    pub struct Clock(u32) {
+        #["Wall-clock time, which may jump"]
+        REALTIME = 0,
+        #["Time since an unspecified point, which never jumps"]
+        MONOTONIC = 1,
+        #["CPU time consumed by the process"]
+        PROCESS_CPU_TIME = 2,
+        #["CPU time consumed by the thread"]
+        THREAD_CPU_TIME = 3,
+        #["Monotonic time, without frequency adjustments"]
+        MONOTONIC_RAW = 4,
+        #["Faster but less precise wall-clock time"]
+        REALTIME_COARSE = 5,
+        #["Faster but less precise monotonic time"]
+        MONOTONIC_COARSE = 6,
+        #["Monotonic time, including time suspended"]
+        BOOT_TIME = 7
+    }
+}
@@ -0,0 +1,310 @@
+use crate::{c_flags, ioctl, Error, Fd, File, FileDescriptor};
+
+/// The API version negotiated with `Userfaultfd::api`.
+pub const API: u64 = 0xAA;
+/// The size of each message read from a userfaultfd.
+pub const MESSAGE_LEN: usize = 32;
+
+pub const UFFDIO_REGISTER: u32 = ioctl!(read + write; 0xAA, 0x00, Register);
+pub const UFFDIO_UNREGISTER: u32 = ioctl!(read; 0xAA, 0x01, Range);
+pub const UFFDIO_WAKE: u32 = ioctl!(read; 0xAA, 0x02, Range);
+pub const UFFDIO_COPY: u32 = ioctl!(read + write; 0xAA, 0x03, Copy);
+pub const UFFDIO_ZEROPAGE: u32 = ioctl!(read + write; 0xAA, 0x04, Zeropage);
+pub const UFFDIO_WRITEPROTECT: u32 = ioctl!(read + write; 0xAA, 0x06, WriteProtect);
+pub const UFFDIO_API: u32 = ioctl!(read + write; 0xAA, 0x3F, Api);
+
+c_flags!{
+    pub Flags(u32) {
+        USER_MODE_ONLY = 0x1,
+        NON_BLOCKING = 0o4000,
+        CLOSE_ON_EXEC = 0o2000000
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub Features(u64) {
+        PAGEFAULT_FLAG_WRITE_PROTECT = 1 << 0,
+        EVENT_FORK = 1 << 1,
+        EVENT_REMAP = 1 << 2,
+        EVENT_REMOVE = 1 << 3,
+        MISSING_HUGETLBFS = 1 << 4,
+        MISSING_SHMEM = 1 << 5,
+        EVENT_UNMAP = 1 << 6,
+        SIGBUS = 1 << 7,
+        THREAD_ID = 1 << 8,
+        MINOR_HUGETLBFS = 1 << 9,
+        MINOR_SHMEM = 1 << 10,
+        EXACT_ADDRESS = 1 << 11,
+        WRITE_PROTECT_HUGETLBFS_SHMEM = 1 << 12,
+        WRITE_PROTECT_UNPOPULATED = 1 << 13,
+        POISON = 1 << 14,
+        WRITE_PROTECT_ASYNC = 1 << 15,
+        MOVE = 1 << 16
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub Ioctls(u64) {
+        REGISTER = 1 << 0x00,
+        UNREGISTER = 1 << 0x01,
+        WAKE = 1 << 0x02,
+        COPY = 1 << 0x03,
+        ZEROPAGE = 1 << 0x04,
+        MOVE = 1 << 0x05,
+        WRITE_PROTECT = 1 << 0x06,
+        CONTINUE = 1 << 0x07,
+        POISON = 1 << 0x08,
+        API = 1 << 0x3F
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub RegisterMode(u64) {
+        MISSING = 0x1,
+        WRITE_PROTECT = 0x2,
+        MINOR = 0x4
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub CopyMode(u64) {
+        DONT_WAKE = 0x1,
+        WRITE_PROTECT = 0x2
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub ZeropageMode(u64) {
+        DONT_WAKE = 0x1
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub WriteProtectMode(u64) {
+        WRITE_PROTECT = 0x1,
+        DONT_WAKE = 0x2
+    } _ => Err(crate::Error::EINVAL)
+}
+
+c_flags!{
+    pub PagefaultFlags(u64) {
+        WRITE = 0x1,
+        WRITE_PROTECT = 0x2,
+        MINOR = 0x4
+    } _ => Err(crate::Error::EINVAL)
+}
+
+/// A page-aligned range of addresses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(C)]
+pub struct Range {
+    pub start: u64,
+    pub length: u64
+}
+impl Range {
+    pub fn new(start: usize, length: usize) -> Self {
+        Self { start: start as u64, length: length as u64 }
+    }
+}
+
+/// The request of `UFFDIO_API`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Api {
+    pub api: u64,
+    pub features: Features,
+    pub ioctls: Ioctls
+}
+
+/// The request of `UFFDIO_REGISTER`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Register {
+    pub range: Range,
+    pub mode: RegisterMode,
+    pub ioctls: Ioctls
+}
+
+/// The request of `UFFDIO_COPY`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Copy {
+    pub destination: u64,
+    pub source: u64,
+    pub length: u64,
+    pub mode: CopyMode,
+    /// The number of bytes copied, or a negative error number.
+    pub copied: i64
+}
+
+/// The request of `UFFDIO_ZEROPAGE`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct Zeropage {
+    pub range: Range,
+    pub mode: ZeropageMode,
+    /// The number of bytes zeroed, or a negative error number.
+    pub zeroed: i64
+}
+
+/// The request of `UFFDIO_WRITEPROTECT`.
+#[derive(Debug, Clone, Copy)]
+#[repr(C)]
+pub struct WriteProtect {
+    pub range: Range,
+    pub mode: WriteProtectMode
+}
+
+/// A userfaultfd, through which page faults in registered ranges are delivered to user space.
+///
+/// Resolving a fault only ever fills pages that are missing, so the ioctls cannot overwrite pages that are present.
+/// A missing page may still be one that Rust code expects to read as zero, which is why `register` is unsafe.
+#[derive(Debug)]
+#[repr(transparent)]
+pub struct Userfaultfd(pub(crate) File);
+impl Userfaultfd {
+    /// Negotiate the API, enabling the requested features.
+    ///
+    /// This must be done before any other ioctl. Returns the features and ioctls available, or `EINVAL` if a
+    /// requested feature is not supported.
+    pub fn api(&self, features: Features) -> Result<Api, Error> {
+        let mut api = Api { api: API, features, ioctls: Ioctls::NONE };
+        unsafe { crate::ioctl(self, UFFDIO_API, &mut api)? };
+        Ok(api)
+    }
+    /// Deliver faults in a range of this process's memory. Returns the ioctls supported on the range.
+    ///
+    /// # Safety
+    /// While the range is registered, `copy` may fill its missing pages with arbitrary data. The range must not
+    /// contain memory that Rust code relies on being zero or unmapped, such as a zeroed allocation that has not been
+    /// touched yet.
+    pub unsafe fn register(&self, range: Range, mode: RegisterMode) -> Result<Ioctls, Error> {
+        let mut register = Register { range, mode, ioctls: Ioctls::NONE };
+        unsafe { crate::ioctl(self, UFFDIO_REGISTER, &mut register)? };
+        Ok(register.ioctls)
+    }
+    pub fn unregister(&self, mut range: Range) -> Result<(), Error> {
+        unsafe { crate::ioctl(self, UFFDIO_UNREGISTER, &mut range) }
+    }
+    /// Wake threads waiting on faults in a range, such as after resolving them with `DONT_WAKE`.
+    pub fn wake(&self, mut range: Range) -> Result<(), Error> {
+        unsafe { crate::ioctl(self, UFFDIO_WAKE, &mut range) }
+    }
+    /// Resolve missing-page faults by atomically copying `source` in to the pages at `destination`.
+    ///
+    /// The length of `source` must be a multiple of the page size. Returns the number of bytes copied, which may be
+    /// short if interrupted. Fails with `EEXIST` if a page is already present.
+    pub fn copy(&self, destination: usize, source: &[u8], mode: CopyMode) -> Result<usize, Error> {
+        let mut copy = Copy {
+            destination: destination as u64,
+            source: source.as_ptr() as u64,
+            length: source.len() as u64,
+            mode,
+            copied: 0
+        };
+        let result = unsafe { crate::ioctl(self, UFFDIO_COPY, &mut copy) };
+        partial(result, copy.copied)
+    }
+    /// Resolve missing-page faults by mapping zeroed pages. Returns the number of bytes zeroed.
+    pub fn zeropage(&self, range: Range, mode: ZeropageMode) -> Result<usize, Error> {
+        let mut zeropage = Zeropage { range, mode, zeroed: 0 };
+        let result = unsafe { crate::ioctl(self, UFFDIO_ZEROPAGE, &mut zeropage) };
+        partial(result, zeropage.zeroed)
+    }
+    /// Write protect a range registered with `RegisterMode::WRITE_PROTECT`, or remove write protection and wake
+    /// the faulting threads when `WriteProtectMode::WRITE_PROTECT` is not set.
+    pub fn write_protect(&self, range: Range, mode: WriteProtectMode) -> Result<(), Error> {
+        let mut write_protect = WriteProtect { range, mode };
+        unsafe { crate::ioctl(self, UFFDIO_WRITEPROTECT, &mut write_protect) }
+    }
+    /// Read pending events in to a buffer.
+    ///
+    /// Blocks until an event is available, unless the userfaultfd was created with `NON_BLOCKING`.
+    pub fn read<'a>(&self, buffer: &'a mut [u8]) -> Result<Events<'a>, Error> {
+        crate::read(self, buffer).map(Events::new)
+    }
+}
+/// The result of an ioctl that reports partial progress as a count or negative error number.
+fn partial(result: Result<(), Error>, count: i64) -> Result<usize, Error> {
+    match result {
+        Ok(()) => Ok(count as usize),
+        // Interrupted part-way, but some bytes were resolved
+        Err(Error::EAGAIN) if count > 0 => Ok(count as usize),
+        Err(e) => Err(e)
+    }
+}
+impl FileDescriptor for Userfaultfd {
+    #[inline(always)]
+    fn raw(&self) -> u32 {
+        self.0.raw()
+    }
+}
+impl<'a> AsRef<Fd<'a>> for Userfaultfd {
+    fn as_ref(&self) -> &'a Fd<'a> {
+        // Safety: Userfaultfd, File and Fd are all `repr(transparent)` over u32
+        unsafe { core::mem::transmute(self) }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Event {
+    /// A thread faulted on a registered range and is waiting for it to be resolved.
+    Pagefault {
+        flags: PagefaultFlags,
+        address: u64,
+        /// The faulting thread, with `Features::THREAD_ID`.
+        thread_id: u32
+    },
+    /// The process forked with `Features::EVENT_FORK`. The new userfaultfd of the child is owned by the reader.
+    Fork {
+        userfaultfd: Fd<'static>
+    },
+    /// A registered range was moved by `mremap`, with `Features::EVENT_REMAP`.
+    Remap {
+        from: u64,
+        to: u64,
+        length: u64
+    },
+    /// Pages were discarded by `madvise`, with `Features::EVENT_REMOVE`.
+    Remove {
+        start: u64,
+        end: u64
+    },
+    /// A registered range was unmapped, with `Features::EVENT_UNMAP`.
+    Unmap {
+        start: u64,
+        end: u64
+    },
+    Unknown(u8)
+}
+
+/// An iterator over the fixed-size messages read from a userfaultfd.
+#[derive(Debug, Clone)]
+pub struct Events<'a>(&'a [u8]);
+impl<'a> Events<'a> {
+    /// Iterate the events in the filled portion of a read buffer.
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self(buffer)
+    }
+}
+impl<'a> Iterator for Events<'a> {
+    type Item = Event;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.len() < MESSAGE_LEN {
+            return None
+        }
+        let (message, rest) = self.0.split_at(MESSAGE_LEN);
+        self.0 = rest;
+        let u64_at = |i: usize| u64::from_ne_bytes(message[i..i + 8].try_into().unwrap());
+        let u32_at = |i: usize| u32::from_ne_bytes(message[i..i + 4].try_into().unwrap());
+        // The event-specific fields follow an 8 byte header
+        Some(match message[0] {
+            0x12 => Event::Pagefault { flags: PagefaultFlags(u64_at(8)), address: u64_at(16), thread_id: u32_at(24) },
+            0x13 => Event::Fork { userfaultfd: Fd::from_raw(u32_at(8)) },
+            0x14 => Event::Remap { from: u64_at(8), to: u64_at(16), length: u64_at(24) },
+            0x15 => Event::Remove { start: u64_at(8), end: u64_at(16) },
+            0x16 => Event::Unmap { start: u64_at(8), end: u64_at(16) },
+            event => Event::Unknown(event)
+        })
+    }
+}
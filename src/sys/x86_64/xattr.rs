@@ -0,0 +1,34 @@
+use crate::c_flags;
+
+c_flags!{
+    pub XattrFlags(u32) {
+        CREATE = 0x1,
+        REPLACE = 0x2
+    } _ => Err(crate::Error::EINVAL)
+}
+
+/// An iterator over the null-separated names written by `listxattr`.
+/// 
+/// Names are yielded without their null terminator.
+#[derive(Debug, Clone)]
+pub struct XattrNames<'a>(&'a [u8]);
+impl<'a> XattrNames<'a> {
+    /// Iterate the names in the filled portion of a `listxattr` buffer.
+    pub fn new(list: &'a [u8]) -> Self {
+        Self(list)
+    }
+}
+impl<'a> Iterator for XattrNames<'a> {
+    type Item = &'a [u8];
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.is_empty() {
+            return None
+        }
+        let (name, rest) = match self.0.iter().position(|&b| b == 0) {
+            Some(end) => (&self.0[..end], &self.0[end + 1..]),
+            None => (self.0, &[][..])
+        };
+        self.0 = rest;
+        Some(name)
+    }
+}
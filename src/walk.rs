@@ -0,0 +1,304 @@
+//! Race-free recursive directory traversal.
+//!
+//! Every directory is opened relative to its parent's file descriptor, and symlinks are never followed unless asked.
+//! Replacing a directory in the tree with a symlink part-way through a walk therefore cannot redirect the walk
+//! outside of the tree.
+use std::{ffi::OsStr, os::unix::prelude::OsStrExt, path::{Path, PathBuf}};
+use crate::{
+    dirent, fchmod, fstat, fstatat, getdents64, mkdirat, open, openat, readlinkat, symlinkat, unlinkat,
+    AtFlags, Error, Fd, File, FileType, Stat, UnlinkFlags
+};
+
+/// The size of the buffer used to read directory entries.
+const BUFFER_LEN: usize = 8192;
+/// The maximum length of a symlink target that can be copied.
+const LINK_LEN: usize = 4096;
+
+/// An entry found during a walk.
+#[derive(Debug, Clone, Copy)]
+pub struct Entry<'a> {
+    /// The directory containing the entry, for use with the `*at` family of syscalls.
+    pub dir: Fd<'a>,
+    pub name: &'a Path,
+    /// The path of the entry relative to the root of the walk.
+    pub path: &'a Path,
+    /// The number of directories between the root of the walk and the entry, starting at 1.
+    pub depth: usize,
+    pub stat: &'a Stat
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Visit<'a> {
+    /// A directory, before its contents are visited.
+    Enter(Entry<'a>),
+    /// A directory, after its contents have been visited.
+    Leave(Entry<'a>),
+    /// A file that will not be descended in to.
+    ///
+    /// This includes directories beyond the maximum depth or on another file system when crossing is disabled.
+    Entry(Entry<'a>),
+    /// A directory that is also one of its own ancestors, reachable only by following symlinks or through bind mounts.
+    Loop(Entry<'a>)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Control {
+    Continue,
+    /// Do not descend in to the directory just entered.
+    Skip,
+    /// End the walk.
+    Stop
+}
+
+/// A depth-first directory walker.
+#[derive(Debug, Clone)]
+pub struct Walk {
+    follow_symlinks: bool,
+    same_file_system: bool,
+    max_depth: usize
+}
+impl Default for Walk {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl Walk {
+    /// A walker that visits everything beneath a directory without following symlinks.
+    pub fn new() -> Self {
+        Self {
+            follow_symlinks: false,
+            same_file_system: false,
+            max_depth: usize::MAX
+        }
+    }
+    /// Follow symlinks, visiting their targets in place of the links.
+    pub fn follow_symlinks(mut self, follow: bool) -> Self {
+        self.follow_symlinks = follow;
+        self
+    }
+    /// Do not descend in to directories on a different file system to the root.
+    pub fn same_file_system(mut self, same: bool) -> Self {
+        self.same_file_system = same;
+        self
+    }
+    /// Do not visit entries deeper than `depth`.
+    pub fn max_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+    /// Walk the contents of the open directory `dir`, calling `f` for each visit.
+    ///
+    /// An error returned by `f` ends the walk. The walk holds one open file descriptor per level of depth.
+    pub fn run<'a, D: AsRef<Fd<'a>>, F: FnMut(Visit) -> Result<Control, Error>>(&self, dir: D, mut f: F) -> Result<(), Error> {
+        // Reopen the directory so that the walk does not share, or disturb, the read position of `dir`
+        let dir = openat(dir, ".", open::Flags::READ_ONLY | open::Flags::DIRECTORY | open::Flags::CLOSE_ON_EXEC, open::Mode::NONE)?;
+        let root = fstat(&dir)?;
+        let mut walker = Walker {
+            options: self,
+            device: root.device_id,
+            path: PathBuf::new(),
+            ancestors: vec![(root.device_id, root.inode)]
+        };
+        walker.directory(&dir.fd(), 1, &mut f).map(|_| ())
+    }
+}
+
+struct Walker<'a> {
+    options: &'a Walk,
+    device: u64,
+    path: PathBuf,
+    ancestors: Vec<(u64, u64)>
+}
+impl<'a> Walker<'a> {
+    fn directory<F: FnMut(Visit) -> Result<Control, Error>>(&mut self, dir: &Fd, depth: usize, f: &mut F) -> Result<Control, Error> {
+        let mut buffer = vec![0; BUFFER_LEN];
+        loop {
+            let entries = getdents64(dir, &mut buffer)?;
+            if entries.is_empty() {
+                return Ok(Control::Continue)
+            }
+            for entry in dirent::Entries::new(entries).filter(|entry| !entry.is_dot()) {
+                let name = Path::new(OsStr::from_bytes(entry.name));
+                self.path.push(name);
+                let control = self.entry(dir, name, depth, f);
+                self.path.pop();
+                if control? == Control::Stop {
+                    return Ok(Control::Stop)
+                }
+            }
+        }
+    }
+    fn entry<F: FnMut(Visit) -> Result<Control, Error>>(&mut self, dir: &Fd, name: &Path, depth: usize, f: &mut F) -> Result<Control, Error> {
+        let stat_flags = if self.options.follow_symlinks { AtFlags::NONE } else { AtFlags::SYMLINK_NO_FOLLOW };
+        let stat = match fstatat(dir, name, stat_flags) {
+            Ok(stat) => stat,
+            // A dangling symlink is visited as the link itself
+            Err(Error::ENOENT) if self.options.follow_symlinks => match fstatat(dir, name, AtFlags::SYMLINK_NO_FOLLOW) {
+                Ok(stat) => stat,
+                Err(Error::ENOENT) => return Ok(Control::Continue),
+                Err(e) => return Err(e)
+            },
+            // Removed since the directory was read
+            Err(Error::ENOENT) => return Ok(Control::Continue),
+            Err(e) => return Err(e)
+        };
+        let descend = stat.file_type() == FileType::DIRECTORY
+            && depth < self.options.max_depth
+            && !(self.options.same_file_system && stat.device_id != self.device);
+        if !descend {
+            return f(Visit::Entry(self.visit(dir, name, depth, &stat)))
+        }
+
+        let mut flags = open::Flags::READ_ONLY | open::Flags::DIRECTORY | open::Flags::CLOSE_ON_EXEC;
+        if !self.options.follow_symlinks {
+            flags |= open::Flags::NO_FOLLOW;
+        }
+        let (child, stat) = match openat(dir, name, flags, open::Mode::NONE) {
+            // The directory may have been swapped since it was inspected, so trust only what was opened
+            Ok(child) => {
+                let stat = fstat(&child)?;
+                (child, stat)
+            },
+            // Replaced with a non-directory since it was inspected
+            Err(Error::ENOTDIR | Error::ELOOP) => return match fstatat(dir, name, stat_flags) {
+                Ok(stat) => f(Visit::Entry(self.visit(dir, name, depth, &stat))),
+                Err(Error::ENOENT) => Ok(Control::Continue),
+                Err(e) => Err(e)
+            },
+            Err(Error::ENOENT) => return Ok(Control::Continue),
+            Err(e) => return Err(e)
+        };
+        let id = (stat.device_id, stat.inode);
+        if self.ancestors.contains(&id) {
+            return f(Visit::Loop(self.visit(dir, name, depth, &stat)))
+        }
+        match f(Visit::Enter(self.visit(dir, name, depth, &stat)))? {
+            Control::Continue => (),
+            Control::Skip => return Ok(Control::Continue),
+            Control::Stop => return Ok(Control::Stop)
+        }
+        self.ancestors.push(id);
+        let control = self.directory(&child.fd(), depth + 1, f);
+        self.ancestors.pop();
+        drop(child);
+        if control? == Control::Stop {
+            return Ok(Control::Stop)
+        }
+        match f(Visit::Leave(self.visit(dir, name, depth, &stat)))? {
+            Control::Stop => Ok(Control::Stop),
+            _ => Ok(Control::Continue)
+        }
+    }
+    fn visit<'b>(&'b self, dir: &Fd<'b>, name: &'b Path, depth: usize, stat: &'b Stat) -> Entry<'b> {
+        Entry {
+            dir: *dir,
+            name,
+            path: &self.path,
+            depth,
+            stat
+        }
+    }
+}
+
+/// Remove a directory and everything beneath it.
+///
+/// `path` is relative to `dir`. If `path` is a symlink the link itself is removed. Symlinks within the tree are
+/// removed rather than followed, so the removal cannot escape the tree even if it is modified concurrently. Returns
+/// `ELOOP` if a directory is reached again within the tree, such as through a bind mount.
+pub fn remove_dir_all<'a, D: AsRef<Fd<'a>>, P: AsRef<Path>>(dir: D, path: P) -> Result<(), Error> {
+    let dir = dir.as_ref();
+    let path = path.as_ref();
+    let flags = open::Flags::READ_ONLY | open::Flags::DIRECTORY | open::Flags::NO_FOLLOW | open::Flags::CLOSE_ON_EXEC;
+    let root = match openat(dir, path, flags, open::Mode::NONE) {
+        Ok(root) => root,
+        Err(e @ (Error::ENOTDIR | Error::ELOOP)) => return match fstatat(dir, path, AtFlags::SYMLINK_NO_FOLLOW)?.file_type() {
+            FileType::SYMLINK => unlinkat(dir, path, UnlinkFlags::NONE),
+            _ => Err(e)
+        },
+        Err(e) => return Err(e)
+    };
+    remove_contents(&root)?;
+    drop(root);
+    unlinkat(dir, path, UnlinkFlags::REMOVE_DIR)
+}
+fn remove_contents(dir: &File) -> Result<(), Error> {
+    Walk::new().run(dir, |visit| {
+        match visit {
+            Visit::Enter(_) => (),
+            Visit::Leave(entry) => match unlinkat(entry.dir, entry.name, UnlinkFlags::REMOVE_DIR) {
+                // Entries created concurrently are not removed
+                Ok(()) | Err(Error::ENOENT) => (),
+                Err(e) => return Err(e)
+            },
+            Visit::Entry(entry) => match unlinkat(entry.dir, entry.name, UnlinkFlags::NONE) {
+                Ok(()) | Err(Error::ENOENT) => (),
+                Err(e) => return Err(e)
+            },
+            // A directory reached again, such as through a bind mount, which cannot be removed from within itself
+            Visit::Loop(_) => return Err(Error::ELOOP)
+        }
+        Ok(Control::Continue)
+    })
+}
+
+/// Copy the contents of the directory `from` in to the directory `to`.
+///
+/// Regular files, directories and symlinks are copied along with their permissions. Other file types are skipped.
+/// Symlinks are copied as links rather than followed. New entries are created exclusively, so an existing entry
+/// in `to`, including a symlink planted there, causes the copy to fail with `EEXIST` rather than being written through.
+pub fn copy_tree<'a, 'b, S: AsRef<Fd<'a>>, D: AsRef<Fd<'b>>>(from: S, to: D) -> Result<(), Error> {
+    let to = to.as_ref();
+    let mut destinations: Vec<File> = Vec::new();
+    Walk::new().run(from, |visit| {
+        let destination = destinations.last().map(File::fd).unwrap_or(*to);
+        match visit {
+            Visit::Enter(entry) => {
+                // Keep the directory writable until its contents are copied
+                let mode = open::Mode(entry.stat.mode & 0o7777);
+                mkdirat(destination, entry.name, mode | open::Mode(0o700))?;
+                let flags = open::Flags::READ_ONLY | open::Flags::DIRECTORY | open::Flags::NO_FOLLOW | open::Flags::CLOSE_ON_EXEC;
+                let dir = openat(destination, entry.name, flags, open::Mode::NONE)?;
+                destinations.push(dir);
+            },
+            Visit::Leave(entry) => {
+                if let Some(dir) = destinations.pop() {
+                    fchmod(&dir, open::Mode(entry.stat.mode & 0o7777))?;
+                }
+            },
+            Visit::Entry(entry) => match entry.stat.file_type() {
+                FileType::REGULAR => copy_file(entry, destination)?,
+                FileType::SYMLINK => {
+                    let mut target = [0; LINK_LEN];
+                    let target = readlinkat(entry.dir, entry.name, &mut target)?;
+                    if target.len() == LINK_LEN {
+                        return Err(Error::ENAMETOOLONG)
+                    }
+                    symlinkat(Path::new(OsStr::from_bytes(target)), destination, entry.name)?;
+                },
+                _ => ()
+            },
+            Visit::Loop(_) => return Err(Error::ELOOP)
+        }
+        Ok(Control::Continue)
+    })
+}
+fn copy_file(entry: Entry, destination: Fd) -> Result<(), Error> {
+    let source = openat(entry.dir, entry.name, open::Flags::READ_ONLY | open::Flags::NO_FOLLOW | open::Flags::CLOSE_ON_EXEC, open::Mode::NONE)?;
+    if fstat(&source)?.file_type() != FileType::REGULAR {
+        // Replaced since it was inspected
+        return Err(Error::EAGAIN)
+    }
+    let flags = open::Flags::WRITE_ONLY | open::Flags::CREATE | open::Flags::EXCLUSIVE | open::Flags::NO_FOLLOW | open::Flags::CLOSE_ON_EXEC;
+    let target = openat(destination, entry.name, flags, open::Mode(entry.stat.mode & 0o7777))?;
+    let mut buffer = vec![0; BUFFER_LEN];
+    loop {
+        match source.read(&mut buffer) {
+            Ok([]) => break,
+            Ok(data) => crate::fs::write_all(&target, data)?,
+            Err(Error::EINTR) => (),
+            Err(e) => return Err(e)
+        }
+    }
+    // The mode passed to open is masked by the umask
+    target.chmod(open::Mode(entry.stat.mode & 0o7777))
+}